@@ -0,0 +1,440 @@
+use crate::command::CommandError;
+use crate::error::ParseError;
+use crate::input::Inputs;
+use crate::registry::Day;
+use crate::solution::Solution;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The seed used when none is given, so anonymising an input twice makes the same one.
+pub const DEFAULT_SEED: u64 = 25;
+
+/// Why an input that's a program, like Intcode, can't be made up: changing any of it stops it
+/// working.
+pub const OPAQUE: &str = "it's a program, which stops working if any of it changes";
+
+/// A small seeded random number generator (SplitMix64), so the same seed always makes up the
+/// same input.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, which can't be empty.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `low..=high`.
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        let span = high.abs_diff(low) as u128 + 1;
+        low.wrapping_add((self.next_u64() as u128 % span) as i64)
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Parses `data` without solving anything, to check that it's an input for the day.
+pub fn check<S: Solution>(data: &str) -> Result<(), ParseError> {
+    S::parse(data).map(drop)
+}
+
+/// Makes up an input like `data` with the day's own rules, for callers that don't know the
+/// day's types.
+pub fn synthesise_with<S: Solution>(data: &str, seed: u64) -> Result<String, &'static str> {
+    S::synthesise(data, &mut Rng::new(seed))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Token<'a> {
+    Number(&'a str),
+    Word(&'a str),
+    Other(char),
+}
+
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    let mut previous = None;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+        let next_is_digit = after.starts_with(|c: char| c.is_ascii_digit());
+        // a minus is a sign unless it joins two things, like the dash in `2-4`
+        let sign = c == '-' && next_is_digit && !previous.is_some_and(char::is_alphanumeric);
+        let (token, length) = if c.is_ascii_digit() || sign {
+            let length = 1 + after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            (Token::Number(&rest[..length]), length)
+        } else if c.is_ascii_alphabetic() {
+            let length = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            (Token::Word(&rest[..length]), length)
+        } else {
+            (Token::Other(c), c.len_utf8())
+        };
+        previous = rest[..length].chars().last();
+        tokens.push(token);
+        rest = &rest[length..];
+    }
+    tokens
+}
+
+/// What the numbers or words in one position of every line look like.
+#[derive(Debug, Default)]
+struct Slot<'a> {
+    low: Option<i64>,
+    high: Option<i64>,
+    /// How many numbers went into `low` and `high`.
+    seen: usize,
+    words: Vec<&'a str>,
+}
+
+/// Words in a position with at most this many different ones are taken to be keywords, like
+/// `forward` or `R`, and kept; any others are made up.
+const KEYWORDS: usize = 16;
+
+/// A number shaped like `original`: the same sign and number of digits, within `low..=high`
+/// when the numbers in its position vary. A number that's the same on every line, like a
+/// fixed size, is part of the format and kept.
+fn make_number(rng: &mut Rng, original: &str, slot: &Slot) -> i64 {
+    let digits = original.trim_start_matches('-').len() as u32;
+    match (slot.low, slot.high) {
+        (Some(low), Some(high)) if low == high && slot.seen > 1 => low,
+        (Some(low), Some(high)) if low < high => rng.range(low, high),
+        _ if digits > 18 => rng.range(0, i64::MAX),
+        _ => {
+            let low = if digits == 1 {
+                0
+            } else {
+                10i64.pow(digits - 1)
+            };
+            let value = rng.range(low, 10i64.pow(digits) - 1);
+            if original.starts_with('-') {
+                -value
+            } else {
+                value
+            }
+        }
+    }
+}
+
+fn make_word(rng: &mut Rng, original: &str, slot: &Slot) -> String {
+    if slot.words.len() <= KEYWORDS {
+        return rng.choose(&slot.words).to_string();
+    }
+    original
+        .chars()
+        .map(|c| {
+            let letter = b'a' + rng.below(26) as u8;
+            if c.is_ascii_uppercase() {
+                letter.to_ascii_uppercase() as char
+            } else {
+                letter as char
+            }
+        })
+        .collect()
+}
+
+/// Makes up an input with the same lines, punctuation and spacing as `data`. The numbers in
+/// each position of a line are made up within the range of the numbers there, keeping their
+/// order within the line, and words come from the ones used in that position.
+pub fn synthesise(data: &str, rng: &mut Rng) -> String {
+    let lines = data.split('\n').map(tokenize).collect::<Vec<_>>();
+
+    let mut numbers = BTreeMap::<usize, Slot>::new();
+    let mut words = BTreeMap::<usize, Slot>::new();
+    for line in &lines {
+        let (mut n, mut w) = (0, 0);
+        for token in line {
+            match *token {
+                Token::Number(number) => {
+                    let slot = numbers.entry(n).or_default();
+                    if let Ok(value) = number.parse::<i64>() {
+                        slot.low = Some(slot.low.map_or(value, |low| low.min(value)));
+                        slot.high = Some(slot.high.map_or(value, |high| high.max(value)));
+                        slot.seen += 1;
+                    }
+                    n += 1;
+                }
+                Token::Word(word) => {
+                    let slot = words.entry(w).or_default();
+                    if !slot.words.contains(&word) {
+                        slot.words.push(word);
+                    }
+                    w += 1;
+                }
+                Token::Other(_) => {}
+            }
+        }
+    }
+    for slot in words.values_mut() {
+        slot.words.sort();
+    }
+
+    let mut out = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let originals = line
+            .iter()
+            .filter_map(|token| match token {
+                Token::Number(number) => Some(*number),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut made = originals
+            .iter()
+            .enumerate()
+            .map(|(n, original)| make_number(rng, original, &numbers[&n]))
+            .collect::<Vec<_>>();
+
+        // hand the made up numbers out in the same order as the real ones, so that a range
+        // like `2-4` still goes up and equal numbers stay equal
+        let key = |original: &str| original.parse::<i64>().ok();
+        let mut order = (0..originals.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| key(originals[i]));
+        let mut sorted = made.clone();
+        sorted.sort();
+        for (rank, &i) in order.iter().enumerate() {
+            made[i] = match rank.checked_sub(1).map(|r| order[r]) {
+                Some(before) if key(originals[before]) == key(originals[i]) => made[before],
+                _ => sorted[rank],
+            };
+        }
+
+        let (mut n, mut w) = (0, 0);
+        let mut text = String::new();
+        for token in line {
+            match *token {
+                Token::Number(_) => {
+                    text.push_str(&made[n].to_string());
+                    n += 1;
+                }
+                Token::Word(word) => {
+                    text.push_str(&make_word(rng, word, &words[&w]));
+                    w += 1;
+                }
+                Token::Other(c) => text.push(c),
+            }
+        }
+        out.push(text);
+    }
+    out.join("\n")
+}
+
+/// Parses the input of each of `days`, failing if any doesn't fit its parser.
+pub fn run_check(days: &[&Day], inputs: &Inputs) -> Result<(), CommandError> {
+    let mut invalid = false;
+    for day in days {
+        let name = format!("{} day {}", day.year, day.day);
+        match inputs.read(day.year, day.day) {
+            Ok(data) => match (day.check)(&data) {
+                Ok(()) => println!("{}: ok, {} lines", name, data.lines().count()),
+                Err(e) => {
+                    println!("{}: invalid input: {}", name, e);
+                    invalid = true;
+                }
+            },
+            Err(e) => println!("{}: no input: {}", name, e),
+        }
+    }
+    if invalid {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+/// Makes up an input shaped like a day's real one and writes it to `out`, or stdout.
+pub fn run(day: &Day, seed: u64, out: Option<&Path>, inputs: &Inputs) -> Result<(), CommandError> {
+    let name = format!("{} day {}", day.year, day.day);
+    let data = inputs.read(day.year, day.day)?;
+    (day.check)(&data)?;
+    let made = match (day.synthesise)(&data, seed) {
+        Ok(made) => made,
+        Err(reason) => {
+            eprintln!("{} can't be anonymised: {}", name, reason);
+            return Err(CommandError::Failed);
+        }
+    };
+    if let Err(e) = (day.check)(&made) {
+        eprintln!("the made up input for {} doesn't parse: {}", name, e);
+        return Err(CommandError::Failed);
+    }
+    match out {
+        Some(path) => {
+            fs::write(path, &made)?;
+            eprintln!("wrote {} lines to {}", made.lines().count(), path.display());
+        }
+        None => print!("{}", made),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{Inputs, DEFAULT_DATA_DIR};
+    use crate::registry::{self, DAYS};
+    use std::cmp::Ordering;
+    use std::panic;
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let rolls = (0..100).map(|_| a.range(-3, 3)).collect::<Vec<_>>();
+        assert_eq!(rolls, (0..100).map(|_| b.range(-3, 3)).collect::<Vec<_>>());
+        assert!(rolls.iter().all(|r| (-3..=3).contains(r)));
+        assert!((-3..=3).all(|r| rolls.contains(&r)));
+        assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+
+        let mut items = (0..10).collect::<Vec<_>>();
+        a.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("2-4,x -3 R75"),
+            [
+                Token::Number("2"),
+                Token::Other('-'),
+                Token::Number("4"),
+                Token::Other(','),
+                Token::Word("x"),
+                Token::Other(' '),
+                Token::Number("-3"),
+                Token::Other(' '),
+                Token::Word("R"),
+                Token::Number("75"),
+            ]
+        );
+        assert_eq!(
+            tokenize("é1 -é"),
+            [
+                Token::Other('é'),
+                Token::Number("1"),
+                Token::Other(' '),
+                Token::Other('-'),
+                Token::Other('é'),
+            ]
+        );
+        let made = synthesise("a é 1\nb ü 22", &mut Rng::new(1));
+        let shape = made.replace(|c: char| c.is_ascii_alphanumeric(), "");
+        assert_eq!(shape, " é \n ü ");
+    }
+
+    #[test]
+    fn test_make_number() {
+        let mut rng = Rng::new(1);
+        let slot = |low, high, seen| Slot {
+            low: Some(low),
+            high: Some(high),
+            seen,
+            words: Vec::new(),
+        };
+        assert_eq!(make_number(&mut rng, "25", &slot(25, 25, 3)), 25);
+        let made = (0..20)
+            .map(|_| make_number(&mut rng, "25", &slot(25, 25, 1)))
+            .collect::<Vec<_>>();
+        assert!(made.iter().all(|n| (10..=99).contains(n)), "{:?}", made);
+        assert!(made.iter().any(|&n| n != 25), "{:?}", made);
+        let made = make_number(&mut rng, "7", &slot(3, 9, 2));
+        assert!((3..=9).contains(&made));
+    }
+
+    #[test]
+    fn test_synthesise() {
+        let data = "2-4,6-8\n2-3,4-5\n\n5-7,7-9\n6-6,4-6\nforward 10\nup 3\n";
+        let made = synthesise(data, &mut Rng::new(1));
+        assert_ne!(made, data);
+        assert_eq!(made.lines().count(), data.lines().count());
+        assert!(made.ends_with('\n'));
+
+        for (real, made) in data.lines().zip(made.lines()) {
+            let shape = |line: &str| line.replace(|c: char| c.is_ascii_digit(), "");
+            let numbers = |line: &str| {
+                line.split(|c: char| !c.is_ascii_digit())
+                    .filter(|n| !n.is_empty())
+                    .map(|n| n.parse::<i64>().unwrap())
+                    .collect::<Vec<_>>()
+            };
+            if real.contains('-') {
+                assert_eq!(shape(made), shape(real));
+                let (real, made) = (numbers(real), numbers(made));
+                assert!(made.iter().all(|n| (2..=9).contains(n)), "{:?}", made);
+                for (a, b) in [(0, 1), (1, 2), (2, 3), (0, 3)] {
+                    match real[a].cmp(&real[b]) {
+                        Ordering::Less => assert!(made[a] <= made[b], "{:?}", made),
+                        Ordering::Equal => assert_eq!(made[a], made[b]),
+                        Ordering::Greater => assert!(made[a] >= made[b], "{:?}", made),
+                    }
+                }
+            } else if !real.is_empty() {
+                let word = |line: &str| line.split(' ').next().unwrap().to_owned();
+                assert!(["forward", "up"].contains(&word(made).as_str()));
+            }
+        }
+        assert_eq!(made, synthesise(data, &mut Rng::new(1)));
+    }
+
+    #[test]
+    fn test_check_rejects_unanswerable_orbits() {
+        let day = registry::find(2019, 6).unwrap();
+        for map in ["COM)B\nB)C\nC)B", "COM)B\nB)X\nA)X", "COM)B\nC)D\nD)C"] {
+            assert!((day.check)(map).is_err(), "{:?}", map);
+        }
+        // a map without YOU and SAN is fine for part 1, and part 2 says what's missing
+        assert_eq!((day.check)("COM)B"), Ok(()));
+        let part2 = day.part(2).unwrap();
+        assert_eq!(
+            part2("COM)B").unwrap_err().to_string(),
+            "expected YOU and SAN on the map"
+        );
+    }
+
+    #[test]
+    fn test_every_day() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        for day in DAYS {
+            let Ok(data) = inputs.read(day.year, day.day) else {
+                continue;
+            };
+            let name = format!("{} day {}", day.year, day.day);
+            assert_eq!((day.check)(&data), Ok(()), "{}", name);
+            let Ok(made) = (day.synthesise)(&data, DEFAULT_SEED) else {
+                continue;
+            };
+            assert_ne!(made, data, "{}", name);
+            assert_eq!(made.lines().count(), data.lines().count(), "{}", name);
+            assert_eq!((day.check)(&made), Ok(()), "{}", name);
+            for part in day.parts() {
+                let solve = day.part(part).unwrap();
+                let answer = panic::catch_unwind(|| solve(&made));
+                assert!(matches!(answer, Ok(Ok(_))), "{} part {}", name, part);
+            }
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use crate::command::{self, CommandError};
+use crate::error::ParseError;
+use crate::input::Inputs;
+use crate::json::Value;
+use crate::registry::Day;
+use crate::solution::Solution;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How many times to run each phase.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Settings {
+    /// Runs thrown away first, to warm caches and the allocator.
+    pub warmup: usize,
+    pub samples: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            warmup: 1,
+            samples: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parse" => Some(Phase::Parse),
+            "part1" => Some(Phase::Part1),
+            "part2" => Some(Phase::Part2),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Stats {
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub samples: usize,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        Self {
+            median,
+            min: samples[0],
+            max: samples[samples.len() - 1],
+            samples: samples.len(),
+        }
+    }
+}
+
+/// Times one phase of one day.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Timing {
+    pub year: u16,
+    pub day: u8,
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+/// Times the phases of a day from its raw input, for callers that don't know the day's types.
+pub type Measure = fn(&str, &Settings, &[u8]) -> Result<Vec<(Phase, Stats)>, ParseError>;
+
+fn sample<T>(settings: &Settings, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..settings.warmup {
+        black_box(f());
+    }
+    let samples = (0..settings.samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            let result = black_box(f());
+            let elapsed = start.elapsed();
+            drop(result);
+            elapsed
+        })
+        .collect();
+    Stats::from_samples(samples)
+}
+
+/// Times parsing `data` and each of `parts` separately. Nothing is timed if `data` doesn't
+/// parse.
+pub fn measure<S: Solution>(
+    data: &str,
+    settings: &Settings,
+    parts: &[u8],
+) -> Result<Vec<(Phase, Stats)>, ParseError> {
+    let input = S::parse(data)?;
+    let mut timings = vec![(Phase::Parse, sample(settings, || S::parse(black_box(data))))];
+    if parts.contains(&1) {
+        timings.push((Phase::Part1, sample(settings, || S::part1(&input))));
+    }
+    if parts.contains(&2) {
+        timings.push((Phase::Part2, sample(settings, || S::part2(&input))));
+    }
+    Ok(timings)
+}
+
+/// Shows a duration in the largest unit that keeps it at least 1.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    match nanos {
+        0..=999 => format!("{}ns", nanos),
+        1_000..=999_999 => format!("{:.2}µs", nanos as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.2}ms", nanos as f64 / 1e6),
+        _ => format!("{:.2}s", nanos as f64 / 1e9),
+    }
+}
+
+pub fn to_json(timings: &[Timing]) -> Value {
+    let results = timings
+        .iter()
+        .map(|t| {
+            Value::object([
+                ("year", Value::from(t.year)),
+                ("day", Value::from(t.day)),
+                ("phase", Value::from(t.phase.name())),
+                ("median_ns", Value::from(t.stats.median.as_nanos() as u64)),
+                ("min_ns", Value::from(t.stats.min.as_nanos() as u64)),
+                ("max_ns", Value::from(t.stats.max.as_nanos() as u64)),
+                ("samples", Value::from(t.stats.samples)),
+            ])
+        })
+        .collect();
+    Value::object([("results", Value::Array(results))])
+}
+
+pub fn from_json(value: &Value) -> Result<Vec<Timing>, String> {
+    let results = value
+        .get("results")
+        .and_then(Value::as_array)
+        .ok_or("expected an object with a results array")?;
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let number = |key| result.get(key).and_then(Value::as_u64);
+            let nanos = |key| number(key).map(Duration::from_nanos);
+            let timing = (|| {
+                Some(Timing {
+                    year: number("year")?.try_into().ok()?,
+                    day: number("day")?.try_into().ok()?,
+                    phase: Phase::from_name(result.get("phase")?.as_str()?)?,
+                    stats: Stats {
+                        median: nanos("median_ns")?,
+                        min: nanos("min_ns")?,
+                        max: nanos("max_ns")?,
+                        samples: number("samples")? as usize,
+                    },
+                })
+            })();
+            timing.ok_or_else(|| format!("result {} is invalid", i))
+        })
+        .collect()
+}
+
+/// A timing next to the same phase from an earlier run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub timing: Timing,
+    pub previous: Option<Duration>,
+    /// Whether the median got slower by more than the threshold.
+    pub regression: bool,
+}
+
+impl Comparison {
+    /// How much the median changed, as a fraction of the earlier one.
+    pub fn change(&self) -> Option<f64> {
+        let previous = self.previous?.as_secs_f64();
+        (previous > 0.0).then(|| self.timing.stats.median.as_secs_f64() / previous - 1.0)
+    }
+}
+
+/// Compares `timings` with `previous`, flagging medians that got slower by more than
+/// `threshold`, a fraction.
+pub fn compare(timings: &[Timing], previous: &[Timing], threshold: f64) -> Vec<Comparison> {
+    timings
+        .iter()
+        .map(|&timing| {
+            let previous = previous
+                .iter()
+                .find(|p| (p.year, p.day, p.phase) == (timing.year, timing.day, timing.phase))
+                .map(|p| p.stats.median);
+            let mut comparison = Comparison {
+                timing,
+                previous,
+                regression: false,
+            };
+            comparison.regression = comparison.change().is_some_and(|c| c > threshold);
+            comparison
+        })
+        .collect()
+}
+
+/// Lays comparisons out as a table.
+pub struct Table<'a>(pub &'a [Comparison]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let compared = self.0.iter().any(|c| c.previous.is_some());
+        write!(
+            f,
+            "year  day  phase  {:>10}  {:>10}  {:>10}",
+            "median", "min", "max"
+        )?;
+        if compared {
+            write!(f, "  {:>10}  change", "before")?;
+        }
+
+        for comparison in self.0 {
+            let timing = &comparison.timing;
+            write!(
+                f,
+                "\n{:<4}  {:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+                timing.year,
+                timing.day,
+                timing.phase.name(),
+                format_duration(timing.stats.median),
+                format_duration(timing.stats.min),
+                format_duration(timing.stats.max)
+            )?;
+            if let Some(previous) = comparison.previous {
+                write!(f, "  {:>10}", format_duration(previous))?;
+                if let Some(change) = comparison.change() {
+                    write!(f, "  {:+.1}%", change * 100.0)?;
+                }
+                if comparison.regression {
+                    write!(f, " REGRESSION")?;
+                }
+            }
+        }
+
+        let regressions = self.0.iter().filter(|c| c.regression).count();
+        if compared {
+            write!(f, "\n{} regressions", regressions)?;
+        }
+        Ok(())
+    }
+}
+
+/// How the bench command samples and what it compares against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOptions {
+    pub settings: Settings,
+    pub save: Option<PathBuf>,
+    pub compare: Option<PathBuf>,
+    /// How much slower a median can get before it counts as a regression, as a fraction.
+    pub threshold: f64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            settings: Settings::default(),
+            save: None,
+            compare: None,
+            threshold: 0.25,
+        }
+    }
+}
+
+/// Times `days`, skipping any without a usable input, and fails if any got slower than the
+/// timings being compared against.
+pub fn run(days: &[&Day], bench: &BenchOptions, inputs: &Inputs) -> Result<(), CommandError> {
+    let previous = match &bench.compare {
+        Some(path) => {
+            let json = Value::parse(&fs::read_to_string(path)?)
+                .and_then(|json| from_json(&json))
+                .map_err(|e| command::usage(format!("{}: {}", path.display(), e)))?;
+            Some(json)
+        }
+        None => None,
+    };
+
+    let mut timings = Vec::new();
+    for day in days {
+        match inputs.read(day.year, day.day) {
+            Ok(data) => match (day.bench)(&data, &bench.settings, &day.parts()) {
+                Ok(measured) => timings.extend(measured.into_iter().map(|(phase, stats)| Timing {
+                    year: day.year,
+                    day: day.day,
+                    phase,
+                    stats,
+                })),
+                Err(e) => eprintln!("skipping {} day {}: {}", day.year, day.day, e),
+            },
+            Err(e) => eprintln!("skipping {} day {}: {}", day.year, day.day, e),
+        }
+    }
+
+    let comparisons = compare(
+        &timings,
+        previous.as_deref().unwrap_or(&[]),
+        bench.threshold,
+    );
+    println!("{}", Table(&comparisons));
+    if let Some(path) = &bench.save {
+        fs::write(path, to_json(&timings).to_string())?;
+    }
+    if comparisons.iter().any(|c| c.regression) {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year2022::day01::Day01;
+
+    fn timing(day: u8, phase: Phase, median_ms: u64) -> Timing {
+        let ms = Duration::from_millis;
+        Timing {
+            year: 2019,
+            day,
+            phase,
+            stats: Stats {
+                median: ms(median_ms),
+                min: ms(median_ms - 1),
+                max: ms(median_ms + 1),
+                samples: 5,
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(vec![ms(5), ms(1), ms(9), ms(3)]);
+        assert_eq!(
+            stats,
+            Stats {
+                median: ms(4),
+                min: ms(1),
+                max: ms(9),
+                samples: 4
+            }
+        );
+        assert_eq!(Stats::from_samples(vec![ms(2), ms(7), ms(1)]).median, ms(2));
+    }
+
+    #[test]
+    fn test_measure() {
+        let settings = Settings {
+            warmup: 0,
+            samples: 3,
+        };
+        let phases = measure::<Day01>("1\n2\n\n3", &settings, &[1])
+            .unwrap()
+            .into_iter()
+            .map(|(phase, stats)| (phase, stats.samples))
+            .collect::<Vec<_>>();
+        assert_eq!(phases, [(Phase::Parse, 3), (Phase::Part1, 3)]);
+        assert!(measure::<Day01>("1\nx", &settings, &[1]).is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let timings = vec![timing(4, Phase::Part1, 30), timing(4, Phase::Part2, 40)];
+        let json = to_json(&timings).to_string();
+        assert!(json.starts_with(
+            r#"{"results":[{"year":2019,"day":4,"phase":"part1","median_ns":30000000,"#
+        ));
+        let parsed = from_json(&Value::parse(&json).unwrap()).unwrap();
+        assert_eq!(parsed, timings);
+        assert!(from_json(&Value::parse(r#"{"results":[{}]}"#).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let previous = [timing(4, Phase::Part1, 10), timing(4, Phase::Part2, 10)];
+        let current = [
+            timing(4, Phase::Part1, 11),
+            timing(4, Phase::Part2, 20),
+            timing(5, Phase::Part1, 10),
+        ];
+        let comparisons = compare(&current, &previous, 0.25);
+        let flagged = comparisons
+            .iter()
+            .map(|c| (c.previous.is_some(), c.regression))
+            .collect::<Vec<_>>();
+        assert_eq!(flagged, [(true, false), (true, true), (false, false)]);
+
+        let table = Table(&comparisons).to_string();
+        assert!(table.contains(
+            "2019    4  part2     20.00ms     19.00ms     21.00ms     10.00ms  +100.0% REGRESSION"
+        ));
+        assert!(table.ends_with("1 regressions"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(812)), "812ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_234)), "1.23µs");
+        assert_eq!(format_duration(Duration::from_micros(4_560)), "4.56ms");
+        assert_eq!(format_duration(Duration::from_millis(1_200)), "1.20s");
+    }
+}
@@ -0,0 +1,781 @@
+use crate::anonymise;
+use crate::bench::{self, BenchOptions};
+use crate::command::{usage, CommandError};
+use crate::download;
+use crate::examples;
+use crate::input::{Inputs, Override};
+use crate::leaderboard;
+use crate::output::Format;
+use crate::registry::{self, Day};
+use crate::runner::{self, resolve, Selection};
+use crate::scaffold;
+use crate::serve;
+use crate::session;
+use crate::status;
+use crate::submit;
+use crate::verify;
+use crate::watch;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: aoc run <year> <day> [<part>] [--format <format>] [--data-dir <dir>] [--input <file>|-]
+       aoc run <year> --all [--format <format>] [--data-dir <dir>]
+       aoc run --all [--jobs <n>] [--format <format>] [--data-dir <dir>]
+       aoc fetch <year> <day> [--data-dir <dir>]
+       aoc fetch <year> --all [--data-dir <dir>]
+       aoc submit <year> <day> <part> [--force] [--data-dir <dir>] [--input <file>|-]
+       aoc verify [<year>] [--data-dir <dir>]
+       aoc bench [<year> [<day>]] [--samples <n>] [--warmup <n>] [--save <file>]
+                 [--compare <file>] [--threshold <percent>] [--data-dir <dir>]
+       aoc new <year> <day> [--data-dir <dir>]
+       aoc examples [<year> [<day>]] [--from <page.html>] [--data-dir <dir>]
+       aoc watch <year> <day> [--data-dir <dir>]
+       aoc status [--markdown] [--data-dir <dir>]
+       aoc leaderboard <export.json> [--day <day>]
+       aoc serve [--port <port>] [--data-dir <dir>]
+       aoc record <year> <day> <session.txt> [--data-dir <dir>] [--input <file>|-]
+       aoc replay <year> <day> <session.txt> [--data-dir <dir>] [--input <file>|-]
+       aoc check [<year> [<day>]] [--data-dir <dir>] [--input <file>|-]
+       aoc anonymise <year> <day> [--seed <n>] [--out <file>] [--data-dir <dir>]
+                     [--input <file>|-]
+
+Runs the solutions for a day, or for every day of a year. Without a part, runs both parts.
+--format is text, json or csv; json and csv give the year, day, part, answer, duration_ns,
+status (ok, no_input, invalid_input or panic) and error of every part; an input a day can't
+parse gets the line and column of the problem. Run exits with 1 unless every part
+gave an answer. Run --all runs every day of every year, several days at once (one per CPU
+unless --jobs says otherwise), and ends with a summary; a day that panics only fails itself.
+Days without a saved input are listed but don't fail run --all.
+Fetch downloads inputs into the data directory; run fetches any that are missing. Submit
+sends a part's answer and keeps every attempt in <dir>/submissions.txt. Answers already
+judged wrong are never resent, and nor are ones outside the bounds set by earlier too high
+and too low answers, unless --force is given. Verify runs every solution and compares it
+with the known answers in <dir>/answers.txt, one `year day part answer` per line.
+
+Bench times parsing and each part separately, reporting the median, min and max of the
+samples. --save writes the timings as JSON; --compare reads an earlier file and flags
+medians that got slower by more than the threshold (25% by default).
+
+New starts a day from a template: it writes src/yearYYYY/dayDD.rs, adds it to the year's
+module (creating the year if it's the first day), registers it, and leaves an empty input
+to paste the puzzle input into.
+
+Examples runs each day against the examples from its puzzle description, kept in
+<dir>/yearYYYY/dayDD.examples.txt as `== part N: answer` lines each followed by the example.
+--from takes them from a saved puzzle page first, replacing the day's file.
+
+Watch reruns a day's tests, examples and both parts through cargo whenever its source, input
+or examples change, showing how each answer changed since the last run.
+
+Status shows a calendar of stars for every year with a solution. A part earns its star once
+its answer is in answers.txt or was accepted by submit. --markdown prints a table for the
+README instead.
+
+Leaderboard reads a private leaderboard saved from its JSON API link and shows the local
+scores and stars of every member, or with --day, when each member finished each part of that
+day after it unlocked and the points they earned.
+
+Serve starts a dashboard at http://127.0.0.1:<port>/ (8025 by default) that lists every day with
+its stars and known answers. A day's page runs each part on the saved input with timings, runs
+a part on pasted or uploaded input, and links any visualisations saved in <dir>/yearYYYY/dayDD/.
+It only listens on the loopback interface and needs no other network.
+
+Record runs a day's Intcode program (2019 days 2, 5 and 25) interactively, one number per
+line on stdin, and saves every input and output with the instruction it happened at to
+<session.txt>, even if the program crashes. Replay runs the program again on the recorded inputs and exits with 1 at
+the first step where it does something different.
+
+Check parses each input with its day's parser without solving it, and exits with 1 if any
+doesn't fit. Anonymise makes up an input shaped like a real one, with the same lines, grammar
+and ranges of values, that tests and benchmarks can share in its place. The same --seed always
+makes the same input. It's written to stdout or --out, and only once the day's parser accepts
+it. Days whose inputs are programs, like Intcode, can't be anonymised.
+
+Inputs are read from <dir>/yearYYYY/dayDD.txt. The directory comes from --data-dir, the
+AOC_DATA_DIR environment variable, data_dir in aoc.conf or ~/.config/aoc/config, or the
+repository's data directory (./data, or the nearest data directory above the executable), in
+that order. --input reads a single day's input from a file, or from stdin when given -.
+
+Downloading needs the session cookie of a logged in browser, from AOC_SESSION or session in
+the config file. AOC_BASE_URL or base_url changes the site it's fetched from.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run(Selection, Format, Options),
+    RunAll {
+        format: Format,
+        workers: usize,
+        options: Options,
+    },
+    Fetch(Selection, Options),
+    Submit {
+        year: u16,
+        day: u8,
+        part: u8,
+        options: Options,
+        force: bool,
+    },
+    Verify(Option<u16>, Options),
+    Bench(Option<Selection>, BenchOptions, Options),
+    New(u16, u8, Options),
+    Watch(u16, u8, Options),
+    Status {
+        markdown: bool,
+        options: Options,
+    },
+    Leaderboard {
+        path: PathBuf,
+        day: Option<u8>,
+    },
+    Serve {
+        port: u16,
+        options: Options,
+    },
+    Record {
+        year: u16,
+        day: u8,
+        path: PathBuf,
+        options: Options,
+    },
+    Replay {
+        year: u16,
+        day: u8,
+        path: PathBuf,
+        options: Options,
+    },
+    Check(Option<Selection>, Options),
+    Anonymise {
+        year: u16,
+        day: u8,
+        seed: u64,
+        out: Option<PathBuf>,
+        options: Options,
+    },
+    Examples {
+        selection: Option<Selection>,
+        page: Option<PathBuf>,
+        options: Options,
+    },
+    Help,
+}
+
+/// Where to read inputs from.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Options {
+    pub data_dir: Option<PathBuf>,
+    pub input: Option<Override>,
+}
+
+fn number<T: std::str::FromStr>(arg: &str, what: &str) -> Result<T, CommandError> {
+    arg.parse()
+        .map_err(|_| usage(format!("{} must be a number, not {:?}", what, arg)))
+}
+
+pub fn parse(args: &[String]) -> Result<Command, CommandError> {
+    let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    match args[..] {
+        [] | ["help" | "--help" | "-h"] => Ok(Command::Help),
+        ["run", ref rest @ ..] => {
+            let (mut rest, options) = parse_options(rest)?;
+            let mut format = Format::Text;
+            if let Some(i) = rest.iter().position(|&a| a == "--format") {
+                let name = rest
+                    .get(i + 1)
+                    .ok_or_else(|| usage("--format needs a value"))?;
+                format = Format::parse(name).ok_or_else(|| {
+                    usage(format!("unknown format {:?}; use text, json or csv", name))
+                })?;
+                rest.drain(i..i + 2);
+            }
+            if let ["--all", ref jobs @ ..] = rest[..] {
+                if options.input.is_some() {
+                    return Err(usage("--input only works with a single day"));
+                }
+                let workers = match jobs {
+                    [] => runner::default_workers(),
+                    ["--jobs", n] => number(n, "jobs")?,
+                    _ => return Err(usage("expected --jobs <n> after --all")),
+                };
+                if workers == 0 {
+                    return Err(usage("jobs must be at least 1"));
+                }
+                return Ok(Command::RunAll {
+                    format,
+                    workers,
+                    options,
+                });
+            }
+            let selection = parse_run(&rest)?;
+            if options.input.is_some() && matches!(selection, Selection::Year(_)) {
+                return Err(usage("--input only works with a single day"));
+            }
+            Ok(Command::Run(selection, format, options))
+        }
+        ["fetch", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("fetch always saves to the data directory"));
+            }
+            match parse_run(&rest)? {
+                Selection::Part(..) => Err(usage("inputs are fetched for a whole day")),
+                selection => Ok(Command::Fetch(selection, options)),
+            }
+        }
+        ["submit", ref rest @ ..] => {
+            let force = rest.contains(&"--force");
+            let rest = rest
+                .iter()
+                .copied()
+                .filter(|&arg| arg != "--force")
+                .collect::<Vec<_>>();
+            let (rest, options) = parse_options(&rest)?;
+            match parse_run(&rest)? {
+                Selection::Part(year, day, part) => Ok(Command::Submit {
+                    year,
+                    day,
+                    part,
+                    options,
+                    force,
+                }),
+                _ => Err(usage("submit needs a year, day and part")),
+            }
+        }
+        ["verify", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("verify reads every input from the data directory"));
+            }
+            match rest[..] {
+                [] => Ok(Command::Verify(None, options)),
+                [year] => Ok(Command::Verify(Some(number(year, "year")?), options)),
+                _ => Err(usage("too many arguments")),
+            }
+        }
+        ["bench", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("bench reads every input from the data directory"));
+            }
+            let mut bench = BenchOptions::default();
+            let mut positional = Vec::new();
+            let mut rest = rest.into_iter();
+            while let Some(arg) = rest.next() {
+                let mut value = || {
+                    rest.next()
+                        .ok_or_else(|| usage(format!("{} needs a value", arg)))
+                };
+                match arg {
+                    "--samples" => bench.settings.samples = number(value()?, "samples")?,
+                    "--warmup" => bench.settings.warmup = number(value()?, "warmup")?,
+                    "--save" => bench.save = Some(PathBuf::from(value()?)),
+                    "--compare" => bench.compare = Some(PathBuf::from(value()?)),
+                    "--threshold" => {
+                        bench.threshold = number::<f64>(value()?, "threshold")? / 100.0
+                    }
+                    _ => positional.push(arg),
+                }
+            }
+            if bench.settings.samples == 0 {
+                return Err(usage("samples must be at least 1"));
+            }
+            let selection = match positional[..] {
+                [] => None,
+                [year] => Some(Selection::Year(number(year, "year")?)),
+                [year, day] => Some(Selection::Day(number(year, "year")?, number(day, "day")?)),
+                _ => return Err(usage("too many arguments")),
+            };
+            Ok(Command::Bench(selection, bench, options))
+        }
+        ["new", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("new leaves the input in the data directory"));
+            }
+            match rest[..] {
+                [year, day] => Ok(Command::New(
+                    number(year, "year")?,
+                    number(day, "day")?,
+                    options,
+                )),
+                _ => Err(usage("new needs a year and a day")),
+            }
+        }
+        ["leaderboard", ref rest @ ..] => match rest[..] {
+            [path] => Ok(Command::Leaderboard {
+                path: PathBuf::from(path),
+                day: None,
+            }),
+            [path, "--day", day] | ["--day", day, path] => Ok(Command::Leaderboard {
+                path: PathBuf::from(path),
+                day: Some(number(day, "day")?),
+            }),
+            _ => Err(usage("leaderboard needs an exported file")),
+        },
+        ["serve", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("serve takes inputs from the page"));
+            }
+            let port = match rest[..] {
+                [] => serve::DEFAULT_PORT,
+                ["--port", port] => number(port, "port")?,
+                _ => return Err(usage("expected --port <port>")),
+            };
+            Ok(Command::Serve { port, options })
+        }
+        [command @ ("record" | "replay"), ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            let [year, day, path] = rest[..] else {
+                return Err(usage(format!(
+                    "{} needs a year, a day and a session file",
+                    command
+                )));
+            };
+            let (year, day, path) = (number(year, "year")?, number(day, "day")?, path.into());
+            Ok(if command == "record" {
+                Command::Record {
+                    year,
+                    day,
+                    path,
+                    options,
+                }
+            } else {
+                Command::Replay {
+                    year,
+                    day,
+                    path,
+                    options,
+                }
+            })
+        }
+        ["check", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            let selection = match rest[..] {
+                [] => None,
+                [year] => Some(Selection::Year(number(year, "year")?)),
+                [year, day] => Some(Selection::Day(number(year, "year")?, number(day, "day")?)),
+                _ => return Err(usage("too many arguments")),
+            };
+            if options.input.is_some() && !matches!(selection, Some(Selection::Day(..))) {
+                return Err(usage("--input only works with a single day"));
+            }
+            Ok(Command::Check(selection, options))
+        }
+        ["anonymise", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            let mut seed = anonymise::DEFAULT_SEED;
+            let mut out = None;
+            let mut positional = Vec::new();
+            let mut rest = rest.into_iter();
+            while let Some(arg) = rest.next() {
+                let mut value = || {
+                    rest.next()
+                        .ok_or_else(|| usage(format!("{} needs a value", arg)))
+                };
+                match arg {
+                    "--seed" => seed = number(value()?, "seed")?,
+                    "--out" => out = Some(PathBuf::from(value()?)),
+                    _ => positional.push(arg),
+                }
+            }
+            match positional[..] {
+                [year, day] => Ok(Command::Anonymise {
+                    year: number(year, "year")?,
+                    day: number(day, "day")?,
+                    seed,
+                    out,
+                    options,
+                }),
+                _ => Err(usage("anonymise needs a year and a day")),
+            }
+        }
+        ["status", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("status reads every input from the data directory"));
+            }
+            match rest[..] {
+                [] => Ok(Command::Status {
+                    markdown: false,
+                    options,
+                }),
+                ["--markdown"] => Ok(Command::Status {
+                    markdown: true,
+                    options,
+                }),
+                _ => Err(usage("status only takes --markdown")),
+            }
+        }
+        ["watch", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("watch reads the input from the data directory"));
+            }
+            match rest[..] {
+                [year, day] => Ok(Command::Watch(
+                    number(year, "year")?,
+                    number(day, "day")?,
+                    options,
+                )),
+                _ => Err(usage("watch needs a year and a day")),
+            }
+        }
+        ["examples", ref rest @ ..] => {
+            let (rest, options) = parse_options(rest)?;
+            if options.input.is_some() {
+                return Err(usage("examples are read from the data directory"));
+            }
+            let (rest, page) = match rest.iter().position(|&a| a == "--from") {
+                Some(i) => {
+                    let page = rest
+                        .get(i + 1)
+                        .ok_or_else(|| usage("--from needs a file"))?;
+                    let page = PathBuf::from(page);
+                    ([&rest[..i], &rest[i + 2..]].concat(), Some(page))
+                }
+                None => (rest, None),
+            };
+            let selection = match rest[..] {
+                [] => None,
+                [year] => Some(Selection::Year(number(year, "year")?)),
+                [year, day] => Some(Selection::Day(number(year, "year")?, number(day, "day")?)),
+                _ => return Err(usage("too many arguments")),
+            };
+            if page.is_some() && !matches!(selection, Some(Selection::Day(..))) {
+                return Err(usage("--from needs a year and a day"));
+            }
+            Ok(Command::Examples {
+                selection,
+                page,
+                options,
+            })
+        }
+        [command, ..] => Err(usage(format!("unknown command {:?}", command))),
+    }
+}
+
+/// Splits the options out of `args`, returning what's left.
+fn parse_options<'a>(args: &[&'a str]) -> Result<(Vec<&'a str>, Options), CommandError> {
+    let mut rest = Vec::new();
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .copied()
+                .ok_or_else(|| usage(format!("{} needs a value", arg)))
+        };
+        match arg {
+            "--data-dir" => options.data_dir = Some(PathBuf::from(value()?)),
+            "--input" => options.input = Some(Override::parse(value()?)),
+            _ => rest.push(arg),
+        }
+    }
+    Ok((rest, options))
+}
+
+fn parse_run(args: &[&str]) -> Result<Selection, CommandError> {
+    match *args {
+        [year, "--all"] => Ok(Selection::Year(number(year, "year")?)),
+        [year, day] => Ok(Selection::Day(number(year, "year")?, number(day, "day")?)),
+        [year, day, part] => {
+            let part = number(part, "part")?;
+            if part != 1 && part != 2 {
+                return Err(usage(format!("part must be 1 or 2, not {}", part)));
+            }
+            Ok(Selection::Part(
+                number(year, "year")?,
+                number(day, "day")?,
+                part,
+            ))
+        }
+        [_] => Err(usage("expected a day or --all after the year")),
+        [] => Err(usage("expected a year")),
+        _ => Err(usage("too many arguments")),
+    }
+}
+
+/// The days a selection covers, or every day without one.
+fn selected_days(selection: Option<Selection>) -> Result<Vec<&'static Day>, CommandError> {
+    Ok(match selection {
+        Some(Selection::Year(year)) => registry::year(year)?,
+        Some(Selection::Day(year, day) | Selection::Part(year, day, _)) => {
+            vec![registry::find(year, day)?]
+        }
+        None => registry::DAYS.iter().collect(),
+    })
+}
+
+impl Options {
+    /// The inputs these options point at.
+    fn inputs(self) -> std::io::Result<Inputs> {
+        Ok(Inputs::configure(self.data_dir)?.with_override(self.input))
+    }
+}
+
+pub fn main(args: &[String]) -> Result<(), CommandError> {
+    match parse(args)? {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Run(selection, format, options) => {
+            runner::run(&resolve(selection)?, &options.inputs()?, format)
+        }
+        Command::RunAll {
+            format,
+            workers,
+            options,
+        } => runner::run_all(&options.inputs()?, workers, format),
+        Command::Fetch(selection, options) => download::run(selection, &options.inputs()?),
+        Command::Submit {
+            year,
+            day,
+            part,
+            options,
+            force,
+        } => submit::run((year, day, part), &options.inputs()?, force),
+        Command::Verify(year, options) => verify::run(
+            &selected_days(year.map(Selection::Year))?,
+            &options.inputs()?,
+        ),
+        Command::Bench(selection, bench, options) => {
+            bench::run(&selected_days(selection)?, &bench, &options.inputs()?)
+        }
+        Command::New(year, day, options) => scaffold::run(year, day, &options.inputs()?),
+        Command::Watch(year, day, options) => watch::run(year, day, &options.inputs()?),
+        Command::Status { markdown, options } => status::run(&options.inputs()?, markdown),
+        Command::Leaderboard { path, day } => leaderboard::run(&path, day),
+        Command::Serve { port, options } => serve::run(port, options.inputs()?),
+        Command::Record {
+            year,
+            day,
+            path,
+            options,
+        } => session::run_record(year, day, &path, &options.inputs()?),
+        Command::Replay {
+            year,
+            day,
+            path,
+            options,
+        } => session::run_replay(year, day, &path, &options.inputs()?),
+        Command::Check(selection, options) => {
+            anonymise::run_check(&selected_days(selection)?, &options.inputs()?)
+        }
+        Command::Anonymise {
+            year,
+            day,
+            seed,
+            out,
+            options,
+        } => anonymise::run(
+            registry::find(year, day)?,
+            seed,
+            out.as_deref(),
+            &options.inputs()?,
+        ),
+        Command::Examples {
+            selection,
+            page,
+            options,
+        } => examples::run(
+            &selected_days(selection)?,
+            page.as_deref(),
+            &options.inputs()?,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let run = |s| match parse(&args(s)).unwrap() {
+            Command::Run(selection, _, _) => selection,
+            other => panic!("expected a run, got {:?}", other),
+        };
+        assert_eq!(parse(&args("")).unwrap(), Command::Help);
+        assert_eq!(
+            parse(&args("examples 2022 3 --from day3.html")).unwrap(),
+            Command::Examples {
+                selection: Some(Selection::Day(2022, 3)),
+                page: Some(PathBuf::from("day3.html")),
+                options: Options::default(),
+            }
+        );
+        assert_eq!(
+            parse(&args("serve --port 9000")).unwrap(),
+            Command::Serve {
+                port: 9000,
+                options: Options::default(),
+            }
+        );
+        assert_eq!(
+            parse(&args("anonymise 2022 4 --seed 7 --out day04.txt")).unwrap(),
+            Command::Anonymise {
+                year: 2022,
+                day: 4,
+                seed: 7,
+                out: Some(PathBuf::from("day04.txt")),
+                options: Options::default(),
+            }
+        );
+        assert_eq!(
+            parse(&args("replay 2019 25 session.txt")).unwrap(),
+            Command::Replay {
+                year: 2019,
+                day: 25,
+                path: PathBuf::from("session.txt"),
+                options: Options::default(),
+            }
+        );
+        assert_eq!(
+            parse(&args("check 2019")).unwrap(),
+            Command::Check(Some(Selection::Year(2019)), Options::default())
+        );
+        assert_eq!(
+            parse(&args("leaderboard board.json --day 3")).unwrap(),
+            Command::Leaderboard {
+                path: PathBuf::from("board.json"),
+                day: Some(3),
+            }
+        );
+        assert_eq!(
+            parse(&args("status --markdown")).unwrap(),
+            Command::Status {
+                markdown: true,
+                options: Options::default(),
+            }
+        );
+        assert_eq!(
+            parse(&args("watch 2024 1")).unwrap(),
+            Command::Watch(2024, 1, Options::default())
+        );
+        assert_eq!(
+            parse(&args("new 2023 1")).unwrap(),
+            Command::New(2023, 1, Options::default())
+        );
+        assert_eq!(run("run 2019 3 2"), Selection::Part(2019, 3, 2));
+        assert_eq!(run("run 2019 3"), Selection::Day(2019, 3));
+        assert_eq!(run("run 2022 --all"), Selection::Year(2022));
+        assert_eq!(
+            parse(&args("run --all --jobs 3 --format json")).unwrap(),
+            Command::RunAll {
+                format: Format::Json,
+                workers: 3,
+                options: Options::default(),
+            }
+        );
+        assert!(matches!(
+            parse(&args("fetch 2022 --all")).unwrap(),
+            Command::Fetch(Selection::Year(2022), _)
+        ));
+        match parse(&args(
+            "bench 2019 4 --samples 3 --threshold 10 --save b.json",
+        ))
+        .unwrap()
+        {
+            Command::Bench(selection, bench, _) => {
+                assert_eq!(selection, Some(Selection::Day(2019, 4)));
+                assert_eq!(bench.settings.samples, 3);
+                assert_eq!(bench.threshold, 0.1);
+                assert_eq!(bench.save, Some(PathBuf::from("b.json")));
+            }
+            other => panic!("expected a bench, got {:?}", other),
+        }
+        assert!(matches!(
+            parse(&args("verify 2019")).unwrap(),
+            Command::Verify(Some(2019), _)
+        ));
+        assert!(matches!(
+            parse(&args("submit 2019 3 2 --force")).unwrap(),
+            Command::Submit {
+                year: 2019,
+                day: 3,
+                part: 2,
+                force: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let command = parse(&args("run --data-dir inputs 2019 3 --input -")).unwrap();
+        let options = Options {
+            data_dir: Some(PathBuf::from("inputs")),
+            input: Some(Override::Stdin),
+        };
+        assert_eq!(
+            command,
+            Command::Run(Selection::Day(2019, 3), Format::Text, options)
+        );
+
+        let command = parse(&args("run 2022 --all --data-dir inputs --format csv")).unwrap();
+        let options = Options {
+            data_dir: Some(PathBuf::from("inputs")),
+            input: None,
+        };
+        assert_eq!(
+            command,
+            Command::Run(Selection::Year(2022), Format::Csv, options)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let message = |s| match parse(&args(s)) {
+            Err(CommandError::Usage(message)) => message,
+            other => panic!("expected a usage error, got {:?}", other),
+        };
+        assert_eq!(message("walk"), "unknown command \"walk\"");
+        assert_eq!(message("run"), "expected a year");
+        assert_eq!(
+            message("run 2019"),
+            "expected a day or --all after the year"
+        );
+        assert_eq!(message("run 2019 x"), "day must be a number, not \"x\"");
+        assert_eq!(message("run 2019 1 3"), "part must be 1 or 2, not 3");
+        assert_eq!(
+            message("examples 2022 --from day.html"),
+            "--from needs a year and a day"
+        );
+        assert_eq!(message("run 2019 1 1 1"), "too many arguments");
+        assert_eq!(
+            message("run 2019 1 --format xml"),
+            "unknown format \"xml\"; use text, json or csv"
+        );
+        assert_eq!(message("run 2019 1 --input"), "--input needs a value");
+        assert_eq!(
+            message("fetch 2019 1 2"),
+            "inputs are fetched for a whole day"
+        );
+        assert_eq!(
+            message("submit 2019 1"),
+            "submit needs a year, day and part"
+        );
+        assert_eq!(
+            message("run 2019 --all --input day01.txt"),
+            "--input only works with a single day"
+        );
+        assert_eq!(
+            message("check 2019 --input day01.txt"),
+            "--input only works with a single day"
+        );
+        assert_eq!(message("anonymise 2019 1 --seed"), "--seed needs a value");
+        assert_eq!(
+            message("anonymise 2019"),
+            "anonymise needs a year and a day"
+        );
+        assert_eq!(
+            message("record 2019 25"),
+            "record needs a year, a day and a session file"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use crate::download::DownloadError;
+use crate::error::ParseError;
+use crate::registry::LookupError;
+use crate::scaffold::ScaffoldError;
+use crate::submit::SubmitError;
+use std::fmt::{Display, Formatter};
+
+/// Why a command didn't finish.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The arguments don't make sense; the usage is worth showing again.
+    Usage(String),
+    Lookup(LookupError),
+    Download(DownloadError),
+    Submit(SubmitError),
+    Scaffold(ScaffoldError),
+    Parse(ParseError),
+    /// The command ran, but what it checked didn't hold; it has already said why.
+    Failed,
+    Io(std::io::Error),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Usage(message) => write!(f, "{}", message),
+            CommandError::Lookup(e) => write!(f, "{}", e),
+            CommandError::Download(e) => write!(f, "{}", e),
+            CommandError::Submit(e) => write!(f, "{}", e),
+            CommandError::Scaffold(e) => write!(f, "{}", e),
+            CommandError::Parse(e) => write!(f, "invalid input: {}", e),
+            CommandError::Failed => write!(f, "failed"),
+            CommandError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<LookupError> for CommandError {
+    fn from(e: LookupError) -> Self {
+        CommandError::Lookup(e)
+    }
+}
+
+impl From<DownloadError> for CommandError {
+    fn from(e: DownloadError) -> Self {
+        CommandError::Download(e)
+    }
+}
+
+impl From<SubmitError> for CommandError {
+    fn from(e: SubmitError) -> Self {
+        CommandError::Submit(e)
+    }
+}
+
+impl From<ScaffoldError> for CommandError {
+    fn from(e: ScaffoldError) -> Self {
+        CommandError::Scaffold(e)
+    }
+}
+
+impl From<ParseError> for CommandError {
+    fn from(e: ParseError) -> Self {
+        CommandError::Parse(e)
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        CommandError::Io(e)
+    }
+}
+
+pub fn usage(message: impl Into<String>) -> CommandError {
+    CommandError::Usage(message.into())
+}
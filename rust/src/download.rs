@@ -0,0 +1,418 @@
+use crate::command::CommandError;
+use crate::http;
+use crate::input::{config_value, Inputs};
+use crate::runner::{Job, Selection};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Environment variable holding the `session` cookie from a logged in browser.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable overriding where puzzles are fetched from.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The least time left between two requests to the site.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Debug)]
+pub enum DownloadError {
+    NoSession,
+    NoSuchDay {
+        year: u16,
+        day: u8,
+    },
+    /// The puzzle unlocks in `seconds` seconds, or the site says it hasn't unlocked yet.
+    Unreleased {
+        year: u16,
+        day: u8,
+        seconds: Option<u64>,
+    },
+    BadSession,
+    RateLimited,
+    Http {
+        status: u16,
+        body: String,
+    },
+    Io(io::Error),
+}
+
+impl Display for DownloadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::NoSession => write!(
+                f,
+                "no session cookie; set {} or session in the config file",
+                SESSION_VAR
+            ),
+            DownloadError::NoSuchDay { year, day } => {
+                write!(f, "{} has no day {}", year, day)
+            }
+            DownloadError::Unreleased {
+                year,
+                day,
+                seconds: Some(seconds),
+            } => write!(
+                f,
+                "day {} of {} unlocks in {}h {:02}m {:02}s",
+                day,
+                year,
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+            DownloadError::Unreleased { year, day, .. } => {
+                write!(f, "day {} of {} hasn't unlocked yet", day, year)
+            }
+            DownloadError::BadSession => {
+                write!(f, "the site didn't accept the session cookie; log in again")
+            }
+            DownloadError::RateLimited => {
+                write!(f, "the site is rate limiting requests; try again later")
+            }
+            DownloadError::Http { status, body } => {
+                write!(f, "the site answered {}: {}", status, body.trim())
+            }
+            DownloadError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl From<io::Error> for DownloadError {
+    fn from(e: io::Error) -> Self {
+        DownloadError::Io(e)
+    }
+}
+
+/// How many days a year has puzzles for.
+pub fn days_in(year: u16) -> u8 {
+    if year >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+/// When a puzzle unlocks, in seconds since the Unix epoch: midnight US Eastern (UTC-5) on its
+/// day in December.
+pub fn unlock_time(year: u16, day: u8) -> u64 {
+    // days since the epoch for a date in the proleptic Gregorian calendar
+    let days_from_civil = |y: i64, m: i64, d: i64| {
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    };
+    (days_from_civil(year as i64, 12, day as i64) * 86400 + 5 * 3600) as u64
+}
+
+/// Checks a puzzle exists and has unlocked at `now`, in seconds since the Unix epoch.
+pub fn check_released(year: u16, day: u8, now: u64) -> Result<(), DownloadError> {
+    if year < 2015 || day == 0 || day > days_in(year) {
+        return Err(DownloadError::NoSuchDay { year, day });
+    }
+    let unlock = unlock_time(year, day);
+    if now < unlock {
+        return Err(DownloadError::Unreleased {
+            year,
+            day,
+            seconds: Some(unlock - now),
+        });
+    }
+    Ok(())
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Fetches puzzle inputs with a session cookie, at most one request per [`MIN_INTERVAL`].
+#[derive(Debug)]
+pub struct Downloader {
+    base_url: String,
+    session: String,
+    min_interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Downloader {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            session: session.into(),
+            min_interval: MIN_INTERVAL,
+            last_request: None,
+        }
+    }
+
+    /// Reads the session from [`SESSION_VAR`] or `session` in the config file, and the base url
+    /// from [`BASE_URL_VAR`] or `base_url`.
+    pub fn configure() -> Result<Self, DownloadError> {
+        let setting = |var: &str, key: &str| -> io::Result<Option<String>> {
+            match std::env::var(var) {
+                Ok(value) if !value.is_empty() => Ok(Some(value)),
+                _ => Ok(config_value(key)?.map(|(value, _)| value)),
+            }
+        };
+        let session = setting(SESSION_VAR, "session")?.ok_or(DownloadError::NoSession)?;
+        let base_url =
+            setting(BASE_URL_VAR, "base_url")?.unwrap_or_else(|| DEFAULT_BASE_URL.to_owned());
+        Ok(Self::new(base_url, session))
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Sends a request to `path` on the site with the session cookie, waiting out the rate
+    /// limit first.
+    pub fn request(
+        &mut self,
+        path: &str,
+        form: Option<&str>,
+    ) -> Result<http::Response, DownloadError> {
+        if let Some(last) = self.last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+
+        let url = format!("{}{}", self.base_url, path);
+        let cookie = format!("session={}", self.session);
+        let headers = [("Cookie", cookie.as_str())];
+        let response = match form {
+            Some(form) => http::post(&url, &headers, form)?,
+            None => http::get(&url, &headers)?,
+        };
+        match response.status {
+            200 => Ok(response),
+            429 => Err(DownloadError::RateLimited),
+            400 | 500 if response.body.contains("log in") => Err(DownloadError::BadSession),
+            status => Err(DownloadError::Http {
+                status,
+                body: response.body,
+            }),
+        }
+    }
+
+    /// Fetches the input for a day from the site.
+    pub fn fetch(&mut self, year: u16, day: u8) -> Result<String, DownloadError> {
+        check_released(year, day, now())?;
+        match self.request(&format!("/{}/day/{}/input", year, day), None) {
+            Ok(response) => Ok(response.body),
+            Err(DownloadError::Http { status: 404, .. }) => Err(DownloadError::Unreleased {
+                year,
+                day,
+                seconds: None,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Makes sure the input for a day is in the data directory, fetching it if it isn't. Inputs
+    /// never change, so a cached file is never fetched again.
+    pub fn download(
+        &mut self,
+        inputs: &Inputs,
+        year: u16,
+        day: u8,
+    ) -> Result<Download, DownloadError> {
+        let path = inputs.path(year, day);
+        if inputs.exists(year, day) {
+            return Ok(Download::Cached(path));
+        }
+
+        let data = self.fetch(year, day)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // write then rename, so an interrupted download never looks cached
+        let partial = path.with_extension("txt.partial");
+        fs::write(&partial, data)?;
+        fs::rename(&partial, &path)?;
+        Ok(Download::Fetched(path))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Download {
+    Cached(PathBuf),
+    Fetched(PathBuf),
+}
+
+/// Downloads the inputs `jobs` need that aren't there yet, if a session is configured and
+/// inputs aren't being read from elsewhere. Anything that can't be fetched is left for the run
+/// to report.
+pub fn fetch_missing(inputs: &Inputs, jobs: &[Job]) {
+    if inputs.has_override() {
+        return;
+    }
+    let mut days = jobs
+        .iter()
+        .map(|job| (job.year, job.day))
+        .filter(|&(year, day)| !inputs.exists(year, day))
+        .collect::<Vec<_>>();
+    days.dedup();
+    if days.is_empty() {
+        return;
+    }
+
+    let Ok(mut downloader) = Downloader::configure() else {
+        return;
+    };
+    for (year, day) in days {
+        match downloader.download(inputs, year, day) {
+            Ok(download) => {
+                if let Download::Fetched(path) = download {
+                    eprintln!("fetched {} day {} to {}", year, day, path.display());
+                }
+            }
+            Err(e) => eprintln!("couldn't fetch {} day {}: {}", year, day, e),
+        }
+    }
+}
+
+/// Downloads a day's input, or every input of a year that has unlocked.
+pub fn run(selection: Selection, inputs: &Inputs) -> Result<(), CommandError> {
+    let mut downloader = Downloader::configure()?;
+    let days = match selection {
+        Selection::Year(year) => (1..=days_in(year))
+            .take_while(|&day| check_released(year, day, now()).is_ok())
+            .map(|day| (year, day))
+            .collect(),
+        Selection::Day(year, day) | Selection::Part(year, day, _) => vec![(year, day)],
+    };
+    for (year, day) in days {
+        match downloader.download(inputs, year, day)? {
+            Download::Cached(path) => {
+                println!("{} day {}: already at {}", year, day, path.display())
+            }
+            Download::Fetched(path) => {
+                println!("{} day {}: saved to {}", year, day, path.display())
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::{respond, serve};
+    use crate::scratch::Scratch;
+
+    fn downloader(url: &str) -> Downloader {
+        Downloader::new(url, "abc123").with_min_interval(Duration::ZERO)
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        // 2019-12-01T05:00:00Z
+        assert_eq!(unlock_time(2019, 1), 1575176400);
+        // 2024-12-25T05:00:00Z
+        assert_eq!(unlock_time(2024, 25), 1735102800);
+    }
+
+    #[test]
+    fn test_check_released() {
+        let unlock = unlock_time(2024, 3);
+        assert!(check_released(2024, 3, unlock).is_ok());
+        assert_eq!(
+            check_released(2024, 3, unlock - 3723)
+                .unwrap_err()
+                .to_string(),
+            "day 3 of 2024 unlocks in 1h 02m 03s"
+        );
+        assert_eq!(
+            check_released(2025, 13, u64::MAX).unwrap_err().to_string(),
+            "2025 has no day 13"
+        );
+        assert!(check_released(2014, 1, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_download() {
+        let (url, requests) = serve(vec![respond(200, "3   4\n4   3\n")]);
+        let dir = Scratch::new("download-fetch");
+        let inputs = Inputs::new(&*dir);
+        let mut downloader = downloader(&url);
+
+        let path = dir.join("year2024/day01.txt");
+        assert_eq!(
+            downloader.download(&inputs, 2024, 1).unwrap(),
+            Download::Fetched(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3   4\n4   3\n");
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2024/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\nCookie: session=abc123\r\n"));
+
+        // the stub only answers once, so this would fail if it went back to the site
+        assert_eq!(
+            downloader.download(&inputs, 2024, 1).unwrap(),
+            Download::Cached(path)
+        );
+    }
+
+    #[test]
+    fn test_download_errors() {
+        let (url, _requests) = serve(vec![
+            respond(
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+            respond(
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+            ),
+            respond(429, ""),
+        ]);
+        let dir = Scratch::new("download-errors");
+        let inputs = Inputs::new(&*dir);
+        let mut downloader = downloader(&url);
+
+        let error = downloader.download(&inputs, 2019, 1).unwrap_err();
+        assert_eq!(error.to_string(), "day 1 of 2019 hasn't unlocked yet");
+        assert!(matches!(
+            downloader.download(&inputs, 2019, 2),
+            Err(DownloadError::BadSession)
+        ));
+        assert!(matches!(
+            downloader.download(&inputs, 2019, 3),
+            Err(DownloadError::RateLimited)
+        ));
+        assert!(!dir.join("year2019").exists());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let (url, _requests) = serve(vec![respond(200, "1"), respond(200, "2")]);
+        let mut downloader =
+            Downloader::new(url, "abc123").with_min_interval(Duration::from_millis(200));
+        let start = Instant::now();
+        downloader.fetch(2019, 1).unwrap();
+        downloader.fetch(2019, 2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Something wrong with a puzzle input, and where it is. Lines and columns count from 1, and
+/// columns count characters; both are 0 when it's about the input as a whole.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// An error about `token`, which has to be a slice of `data`, at the place it starts.
+    pub fn at(data: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize).wrapping_sub(data.as_ptr() as usize);
+        let before = data.get(..offset).unwrap_or(data);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self::new(
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+            message,
+        )
+    }
+
+    /// An error about the input as a whole rather than a place in it, like a part finding that
+    /// the input has no answer.
+    pub fn whole(message: impl Into<String>) -> Self {
+        Self::new(0, 0, message)
+    }
+
+    /// An error about something missing from the end of `line`, a line of `data`.
+    pub fn after(data: &str, line: &str, message: impl Into<String>) -> Self {
+        Self::at(data, &line[line.len()..], message)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}", self.message);
+        }
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `token`, a slice of `data`, as a number.
+pub fn number<T: FromStr>(data: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at(data, token, format!("expected a number, found {:?}", token)))
+}
+
+/// Splits `line`, a line of `data`, in two at the first `separator`.
+pub fn split_once<'a>(
+    data: &str,
+    line: &'a str,
+    separator: char,
+) -> Result<(&'a str, &'a str), ParseError> {
+    line.split_once(separator)
+        .ok_or_else(|| ParseError::after(data, line, format!("expected {:?}", separator)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let data = "12\n3x4\nβγ 5";
+        assert_eq!(
+            ParseError::at(data, &data[..2], "a"),
+            ParseError::new(1, 1, "a")
+        );
+        assert_eq!(
+            ParseError::at(data, &data[4..5], "x"),
+            ParseError::new(2, 2, "x")
+        );
+        let last = data.lines().last().unwrap();
+        assert_eq!(
+            ParseError::at(data, &last[5..], "5"),
+            ParseError::new(3, 4, "5")
+        );
+        assert_eq!(
+            ParseError::after(data, last, "end"),
+            ParseError::new(3, 5, "end")
+        );
+        assert_eq!(ParseError::whole("no answer").to_string(), "no answer");
+        assert_eq!(
+            ParseError::new(2, 2, "expected a number").to_string(),
+            "line 2, column 2: expected a number"
+        );
+    }
+
+    #[test]
+    fn test_number() {
+        let data = "10-2x";
+        assert_eq!(number::<i32>(data, &data[..2]), Ok(10));
+        assert_eq!(
+            number::<i32>(data, &data[3..]),
+            Err(ParseError::new(1, 4, "expected a number, found \"2x\""))
+        );
+        assert_eq!(
+            split_once(data, data, ','),
+            Err(ParseError::new(1, 6, "expected ','"))
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use crate::command::{self, CommandError};
+use crate::input::Inputs;
+use crate::registry::Day;
+use crate::verify::{Outcome, Report, Table};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An example from a puzzle description and the answer it gives for one part.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Example {
+    pub part: u8,
+    pub answer: String,
+    pub input: String,
+}
+
+/// The examples for one day, kept next to its input as `dayDD.examples.txt`. Each example is a
+/// `== part N: answer` line followed by the example input.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Examples(pub Vec<Example>);
+
+const HEADER: &str = "== part ";
+
+impl Examples {
+    pub fn path(inputs: &Inputs, year: u16, day: u8) -> PathBuf {
+        inputs.path(year, day).with_extension("examples.txt")
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut examples: Vec<Example> = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            if let Some(header) = line.strip_prefix(HEADER) {
+                let (part, answer) = header
+                    .split_once(": ")
+                    .and_then(|(part, answer)| Some((part.parse().ok()?, answer)))
+                    .ok_or_else(|| format!("line {}: invalid header {:?}", number + 1, line))?;
+                examples.push(Example {
+                    part,
+                    answer: answer.to_owned(),
+                    input: String::new(),
+                });
+            } else if let Some(example) = examples.last_mut() {
+                example.input.push_str(line);
+                example.input.push('\n');
+            } else if !line.trim().is_empty() {
+                return Err(format!(
+                    "line {}: expected a {:?} header",
+                    number + 1,
+                    HEADER
+                ));
+            }
+        }
+        Ok(Self(examples))
+    }
+
+    /// Loads the examples, or none if there isn't a file yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Takes the examples from a saved puzzle page. Each part's example is the first block of
+    /// preformatted code in its description, or part 1's when part 2 doesn't have one, and its
+    /// answer is the last emphasised code. Parts missing either are left out.
+    pub fn extract(html: &str) -> Self {
+        let mut examples = Vec::new();
+        let mut previous = None;
+        for (part, article) in (1..).zip(elements(html, "<article", "</article>")) {
+            let input = elements(article, "<pre><code>", "</code></pre>")
+                .next()
+                .map(text)
+                .or(previous);
+            let answer = elements(article, "<code><em>", "</em></code>")
+                .last()
+                .map(text);
+            if let (Some(input), Some(answer)) = (&input, answer) {
+                examples.push(Example {
+                    part,
+                    answer,
+                    input: input.clone(),
+                });
+            }
+            previous = input;
+        }
+        Self(examples)
+    }
+}
+
+impl Display for Examples {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for example in &self.0 {
+            writeln!(f, "{}{}: {}", HEADER, example.part, example.answer)?;
+            write!(f, "{}", example.input)?;
+            if !example.input.ends_with('\n') {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The contents of every `open … close` element in `html`, skipping the rest of the opening
+/// tag when `open` doesn't close it.
+fn elements<'a>(html: &'a str, open: &'a str, close: &'a str) -> impl Iterator<Item = &'a str> {
+    let mut rest = html;
+    std::iter::from_fn(move || {
+        let start = rest.find(open)? + open.len();
+        let start = if open.ends_with('>') {
+            start
+        } else {
+            start + rest[start..].find('>')? + 1
+        };
+        let end = start + rest[start..].find(close)?;
+        let element = &rest[start..end];
+        rest = &rest[end + close.len()..];
+        Some(element)
+    })
+}
+
+/// Strips the tags from `html` and decodes its entities.
+fn text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = rest[start..].split_once('>').map_or("", |(_, rest)| rest);
+    }
+    text.push_str(rest);
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Runs each example through its part of `day`.
+pub fn check(day: &Day, examples: &Examples) -> Vec<Report> {
+    examples
+        .0
+        .iter()
+        .map(|example| {
+            let (answer, outcome) = match day.part(example.part).map(|solve| solve(&example.input))
+            {
+                Some(Ok(answer)) if answer == example.answer => (Some(answer), Outcome::Pass),
+                Some(Ok(answer)) => (
+                    Some(answer),
+                    Outcome::Fail {
+                        expected: example.answer.clone(),
+                    },
+                ),
+                Some(Err(e)) => (None, Outcome::Invalid(e.to_string())),
+                None => (None, Outcome::Missing),
+            };
+            Report {
+                year: day.year,
+                day: day.day,
+                part: example.part,
+                answer,
+                outcome,
+            }
+        })
+        .collect()
+}
+
+/// Checks `days` against their examples, after saving the ones on `page` if given.
+pub fn run(days: &[&Day], page: Option<&Path>, inputs: &Inputs) -> Result<(), CommandError> {
+    if let Some(page) = page {
+        let day = days[0];
+        let extracted = Examples::extract(&fs::read_to_string(page)?);
+        if extracted.0.is_empty() {
+            return Err(command::usage(format!(
+                "found no examples with answers in {}",
+                page.display()
+            )));
+        }
+        let path = Examples::path(inputs, day.year, day.day);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, extracted.to_string())?;
+        eprintln!("saved {} examples to {}", extracted.0.len(), path.display());
+    }
+
+    let mut reports = Vec::new();
+    for day in days {
+        let examples = Examples::load(&Examples::path(inputs, day.year, day.day))?;
+        reports.extend(check(day, &examples));
+    }
+    println!("{}", Table(&reports));
+    if reports.iter().any(|r| r.outcome != Outcome::Pass) {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::DEFAULT_DATA_DIR;
+    use crate::registry::DAYS;
+    use crate::verify::Table;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 3: Rucksack Reorganization ---</h2>
+<p>For example, suppose you have the following list:</p>
+<pre><code>vJrwpWtwJgWrhcsFMMfFFhFp
+PmmdzqPrVvPwwTWBwg &amp; &lt;<em>x</em>&gt;
+</code></pre>
+<p>The first rucksack contains <code>vJrwpWtwJgWrhcsFMMfFFhFp</code>, which has <code><em>p</em></code>.</p>
+<p>The sum of the priorities of these item types is <code><em>157</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>7701</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>The sum of the priorities is <code><em>70</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_extract() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp\nPmmdzqPrVvPwwTWBwg & <x>\n".to_owned();
+        let examples = Examples::extract(PAGE);
+        assert_eq!(
+            examples,
+            Examples(vec![
+                Example {
+                    part: 1,
+                    answer: "157".to_owned(),
+                    input: input.clone(),
+                },
+                Example {
+                    part: 2,
+                    answer: "70".to_owned(),
+                    input,
+                },
+            ])
+        );
+        assert_eq!(Examples::extract("<p>no puzzle</p>"), Examples::default());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let examples = Examples::extract(PAGE);
+        let text = examples.to_string();
+        assert!(text.starts_with("== part 1: 157\nvJrwpWtwJgWrhcsFMMfFFhFp\n"));
+        assert_eq!(Examples::parse(&text), Ok(examples));
+        assert_eq!(
+            Examples::parse("1\n== part 1: 2"),
+            Err("line 1: expected a \"== part \" header".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_examples() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        let mut reports = Vec::new();
+        for day in DAYS {
+            let examples = Examples::load(&Examples::path(&inputs, day.year, day.day)).unwrap();
+            reports.extend(check(day, &examples));
+        }
+        assert!(!reports.is_empty());
+        assert!(
+            reports.iter().all(|r| r.outcome == Outcome::Pass),
+            "\n{}",
+            Table(&reports)
+        );
+    }
+}
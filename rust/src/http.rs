@@ -0,0 +1,368 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sent with every request so the site can tell who is making them.
+pub const USER_AGENT: &str = concat!(
+    "aoc/",
+    env!("CARGO_PKG_VERSION"),
+    " (github.com/hanhossain/advent-of-code)"
+);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+pub fn get(url: &str, headers: &[(&str, &str)]) -> io::Result<Response> {
+    request("GET", url, headers, None)
+}
+
+/// Posts `body` as `application/x-www-form-urlencoded`.
+pub fn post(url: &str, headers: &[(&str, &str)], body: &str) -> io::Result<Response> {
+    request("POST", url, headers, Some(body))
+}
+
+/// Encodes `pairs` as a form body.
+pub fn form_encode(pairs: &[(&str, &str)]) -> String {
+    let encode = |s: &str| {
+        s.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (b as char).to_string()
+                }
+                b' ' => "+".to_owned(),
+                b => format!("%{:02X}", b),
+            })
+            .collect::<String>()
+    };
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Decodes a form body, or a query string, into its pairs.
+pub fn form_decode(s: &str) -> Vec<(String, String)> {
+    let decode = |s: &str| {
+        let mut bytes = Vec::with_capacity(s.len());
+        let mut rest = s.as_bytes();
+        while let Some((&b, tail)) = rest.split_first() {
+            rest = tail;
+            match b {
+                b'+' => bytes.push(b' '),
+                b'%' => {
+                    let hex = rest.get(..2).and_then(|h| std::str::from_utf8(h).ok());
+                    match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                        Some(byte) => {
+                            bytes.push(byte);
+                            rest = &rest[2..];
+                        }
+                        None => bytes.push(b'%'),
+                    }
+                }
+                b => bytes.push(b),
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+    s.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(k), decode(v))
+        })
+        .collect()
+}
+
+// std has no TLS, so https goes through curl while plain http (the stub servers in tests) is
+// spoken directly
+fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    match url.split_once("://") {
+        Some(("http", rest)) => plain(method, rest, headers, body),
+        Some(("https", _)) => curl(method, url, headers, body),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported url {:?}", url),
+        )),
+    }
+}
+
+fn plain(
+    method: &str,
+    rest: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    let (host, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') {
+        host.to_owned()
+    } else {
+        format!("{}:80", host)
+    };
+    let address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("unknown host {}", host)))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nConnection: close\r\n",
+        method, path, host, USER_AGENT
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if let Some(body) = body {
+        request.push_str("Content-Type: application/x-www-form-urlencoded\r\n");
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    request.push_str(body.unwrap_or(""));
+    stream.write_all(request.as_bytes())?;
+
+    read_response(BufReader::new(stream))
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn read_response(mut reader: impl BufRead) -> io::Result<Response> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid(format!("bad status line {:?}", line.trim_end())))?;
+
+    let mut length = None;
+    let mut chunked = false;
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            match name.to_ascii_lowercase().as_str() {
+                "content-length" => length = value.parse::<usize>().ok(),
+                "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+                _ => {}
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    if chunked {
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let size = line.trim().split(';').next().unwrap_or("");
+            let size = usize::from_str_radix(size, 16)
+                .map_err(|_| invalid(format!("bad chunk size {:?}", size)))?;
+            if size == 0 {
+                break;
+            }
+            let start = body.len();
+            body.resize(start + size, 0);
+            reader.read_exact(&mut body[start..])?;
+            line.clear();
+            reader.read_line(&mut line)?;
+        }
+    } else if let Some(length) = length {
+        body.resize(length, 0);
+        reader.read_exact(&mut body)?;
+    } else {
+        reader.read_to_end(&mut body)?;
+    }
+
+    let body = String::from_utf8(body).map_err(|_| invalid("the body isn't utf-8"))?;
+    Ok(Response { status, body })
+}
+
+fn curl(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    // headers go through a config file on stdin so the session cookie never shows up in `ps`
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut config = format!("url = {}\nuser-agent = {}\n", quote(url), quote(USER_AGENT));
+    for (name, value) in headers {
+        config.push_str(&format!(
+            "header = {}\n",
+            quote(&format!("{}: {}", name, value))
+        ));
+    }
+
+    let mut command = Command::new("curl");
+    command.args(["--silent", "--show-error", "--request", method]);
+    command.args(["--write-out", "\n%{http_code}", "--config", "-"]);
+    // a stalled server would otherwise hang the command, as it can't with the plain http path
+    let timeout = TIMEOUT.as_secs().to_string();
+    command.args(["--connect-timeout", &timeout, "--max-time", &timeout]);
+    if let Some(body) = body {
+        command.args(["--data-binary", body]);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("https needs curl on the PATH: {}", e)))?;
+    child.stdin.take().unwrap().write_all(config.as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("curl failed: {}", error.trim())));
+    }
+    let output = String::from_utf8(output.stdout).map_err(|_| invalid("the body isn't utf-8"))?;
+    let (body, status) = output
+        .rsplit_once('\n')
+        .ok_or_else(|| invalid("curl didn't report a status"))?;
+    let status = status
+        .parse()
+        .map_err(|_| invalid(format!("bad status {:?}", status)))?;
+    Ok(Response {
+        status,
+        body: body.to_owned(),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    /// Serves `responses` in order on a local port, one per connection, and passes on each raw
+    /// request it receives. Returns the base url.
+    pub(crate) fn serve(responses: Vec<String>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8(body).unwrap());
+
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+                let _ = sender.send(request);
+            }
+        });
+        (url, receiver)
+    }
+
+    pub(crate) fn respond(status: u16, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {} OK\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn test_get() {
+        let (url, requests) = serve(vec![respond(200, "1\n2\n")]);
+        let response = get(&format!("{}/2019/day/1/input", url), &[("Cookie", "a=b")]).unwrap();
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                body: "1\n2\n".to_owned()
+            }
+        );
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2019/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\nCookie: a=b\r\n"));
+    }
+
+    #[test]
+    fn test_post() {
+        let (url, requests) = serve(vec![respond(200, "ok")]);
+        let body = form_encode(&[("level", "1"), ("answer", "a b&c")]);
+        assert_eq!(body, "level=1&answer=a+b%26c");
+        post(&format!("{}/answer", url), &[], &body).unwrap();
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=1&answer=a+b%26c"));
+    }
+
+    #[test]
+    fn test_form_decode() {
+        let pairs = [("level", "1"), ("answer", "a b&c"), ("input", "1\n2 ⭐%")];
+        let encoded = form_encode(&pairs);
+        let decoded = form_decode(&encoded);
+        assert_eq!(
+            decoded
+                .iter()
+                .map(|(k, v)| (&**k, &**v))
+                .collect::<Vec<_>>(),
+            pairs
+        );
+        assert_eq!(
+            form_decode("a=%zz&b"),
+            [
+                ("a".to_owned(), "%zz".to_owned()),
+                ("b".to_owned(), String::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunked() {
+        let response = "HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n\
+                        5\r\nhello\r\n7;x=y\r\n, world\r\n0\r\n\r\n";
+        let response = read_response(response.as_bytes()).unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, "hello, world");
+    }
+
+    #[test]
+    fn test_unsupported_url() {
+        let error = get("ftp://example.com", &[]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,269 @@
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that points at the directory holding `yearYYYY/dayDD.txt`.
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+/// Config file looked up in the working directory, then in `~/.config/aoc/config`. It holds
+/// `key = value` lines; `data_dir` is relative to the file's own directory, and a leading `~`
+/// in it is the home directory.
+pub const CONFIG_FILE: &str = "aoc.conf";
+
+/// The inputs that ship with the repository, for tests.
+#[cfg(test)]
+pub(crate) const DEFAULT_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
+
+/// The directory the repository keeps its inputs in.
+const DATA_DIR: &str = "data";
+
+/// Reads a single input from somewhere other than the data directory.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Override {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Override {
+    /// `-` means stdin, anything else is a path.
+    pub fn parse(arg: &str) -> Self {
+        match arg {
+            "-" => Override::Stdin,
+            path => Override::File(PathBuf::from(path)),
+        }
+    }
+}
+
+/// Finds and reads puzzle inputs.
+#[derive(Debug)]
+pub struct Inputs {
+    root: PathBuf,
+    over: Option<Override>,
+    stdin: OnceLock<String>,
+}
+
+impl Inputs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            over: None,
+            stdin: OnceLock::new(),
+        }
+    }
+
+    /// Uses the data directory from `data_dir` if given, otherwise from [`DATA_DIR_VAR`], the
+    /// config file, or the repository's own `data` directory, in that order.
+    pub fn configure(data_dir: Option<PathBuf>) -> io::Result<Self> {
+        let root = match data_dir {
+            Some(dir) => dir,
+            None => match std::env::var_os(DATA_DIR_VAR) {
+                Some(dir) => PathBuf::from(dir),
+                None => match config_value("data_dir")? {
+                    Some((dir, config)) => {
+                        let home = std::env::var_os("HOME").map(PathBuf::from);
+                        let dir = expand_home(&dir, home.as_deref());
+                        config.parent().unwrap_or(Path::new("")).join(dir)
+                    }
+                    None => default_data_dir(),
+                },
+            },
+        };
+        Ok(Self::new(root))
+    }
+
+    /// Reads every input from `over` instead of the data directory.
+    pub fn with_override(mut self, over: Option<Override>) -> Self {
+        self.over = over;
+        self
+    }
+
+    /// Whether inputs come from an override rather than the data directory.
+    pub fn has_override(&self) -> bool {
+        self.over.is_some()
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where the input for a day lives under the data directory.
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.root
+            .join(format!("year{}", year))
+            .join(format!("day{:02}.txt", day))
+    }
+
+    /// Whether the input for a day is in the data directory. An empty file, like the placeholder
+    /// `aoc new` leaves, doesn't count.
+    pub fn exists(&self, year: u16, day: u8) -> bool {
+        std::fs::metadata(self.path(year, day)).is_ok_and(|m| m.len() > 0)
+    }
+
+    pub fn read(&self, year: u16, day: u8) -> io::Result<String> {
+        let path = match &self.over {
+            Some(Override::Stdin) => return self.read_stdin(),
+            Some(Override::File(path)) => path.clone(),
+            None => self.path(year, day),
+        };
+        let data = std::fs::read_to_string(&path).and_then(|data| {
+            if data.is_empty() {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the file is empty",
+                ))
+            } else {
+                Ok(data)
+            }
+        });
+        data.map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "could not read {}: {}; put your puzzle input there, set AOC_SESSION to download it, or pass --data-dir or --input",
+                    path.display(),
+                    e
+                ),
+            )
+        })
+    }
+
+    // stdin can only be read once, but both parts of a day need it
+    fn read_stdin(&self) -> io::Result<String> {
+        if let Some(data) = self.stdin.get() {
+            return Ok(data.clone());
+        }
+        let mut data = String::new();
+        io::stdin().read_to_string(&mut data)?;
+        Ok(self.stdin.get_or_init(|| data).clone())
+    }
+}
+
+/// The repository's `data` directory: the one in the working directory, as when running from
+/// the crate, or else the nearest one above the executable, as for `target/release/aoc`.
+fn default_data_dir() -> PathBuf {
+    let local = PathBuf::from(DATA_DIR);
+    if local.is_dir() {
+        return local;
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.ancestors()
+                .skip(1)
+                .map(|dir| dir.join(DATA_DIR))
+                .find(|dir| dir.is_dir())
+        })
+        .unwrap_or(local)
+}
+
+/// Replaces a leading `~` in `dir` with `home`, as a shell would.
+fn expand_home(dir: &str, home: Option<&Path>) -> PathBuf {
+    match (dir.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(dir),
+    }
+}
+
+/// Looks `key` up in the first config file that exists, returning its value and the file it
+/// came from.
+pub fn config_value(key: &str) -> io::Result<Option<(String, PathBuf)>> {
+    let mut candidates = vec![PathBuf::from(CONFIG_FILE)];
+    if let Some(home) = std::env::var_os("HOME") {
+        candidates.push(Path::new(&home).join(".config/aoc/config"));
+    }
+
+    for path in candidates {
+        match std::fs::read_to_string(&path) {
+            Ok(config) => return Ok(parse_config(&config, key).map(|value| (value, path))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
+/// Finds `key` in `key = value` lines, ignoring blank lines and `#` comments.
+pub(crate) fn parse_config(config: &str, key: &str) -> Option<String> {
+    config
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim().trim_matches('"').to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let inputs = Inputs::new("/inputs");
+        assert_eq!(
+            inputs.path(2019, 3),
+            PathBuf::from("/inputs/year2019/day03.txt")
+        );
+        assert_eq!(
+            inputs.path(2024, 25),
+            PathBuf::from("/inputs/year2024/day25.txt")
+        );
+    }
+
+    #[test]
+    fn test_read() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        assert!(inputs
+            .read(2024, 1)
+            .unwrap()
+            .starts_with(|c: char| c.is_ascii_digit()));
+
+        let error = inputs.read(2019, 24).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("year2019/day24.txt"));
+    }
+
+    #[test]
+    fn test_override() {
+        let file = PathBuf::from(DEFAULT_DATA_DIR).join("year2019/day04.txt");
+        let inputs =
+            Inputs::new("/nowhere").with_override(Some(Override::parse(file.to_str().unwrap())));
+        assert_eq!(inputs.read(2022, 1).unwrap().trim(), "138241-674034");
+        assert!(inputs.has_override());
+        assert!(!Inputs::new("/nowhere").has_override());
+        assert_eq!(Override::parse("-"), Override::Stdin);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = "\
+# where my inputs live
+session = abc
+
+data_dir = \"~/puzzles\"";
+        assert_eq!(
+            parse_config(config, "data_dir"),
+            Some("~/puzzles".to_owned())
+        );
+        assert_eq!(parse_config(config, "session"), Some("abc".to_owned()));
+        assert_eq!(parse_config(config, "missing"), None);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = Some(Path::new("/home/elf"));
+        assert_eq!(
+            expand_home("~/puzzles", home),
+            PathBuf::from("/home/elf/puzzles")
+        );
+        assert_eq!(expand_home("~", home), PathBuf::from("/home/elf"));
+        assert_eq!(
+            expand_home("~elf/puzzles", home),
+            PathBuf::from("~elf/puzzles")
+        );
+        assert_eq!(expand_home("puzzles", home), PathBuf::from("puzzles"));
+        assert_eq!(expand_home("~/puzzles", None), PathBuf::from("~/puzzles"));
+    }
+}
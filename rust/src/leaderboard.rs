@@ -0,0 +1,331 @@
+use crate::command::CommandError;
+use crate::download::{days_in, unlock_time};
+use crate::json::Value;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// When a member got a star, in seconds since the Unix epoch. The index breaks ties between
+/// stars got in the same second.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Star {
+    pub time: u64,
+    pub index: u64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    /// Stars by day and part.
+    pub stars: BTreeMap<(u8, u8), Star>,
+}
+
+impl Member {
+    /// How long after the puzzle unlocked the member got the star, in seconds.
+    pub fn completion(&self, year: u16, day: u8, part: u8) -> Option<u64> {
+        let star = self.stars.get(&(day, part))?;
+        Some(star.time.saturating_sub(unlock_time(year, day)))
+    }
+
+    /// How long the member took from part 1 to part 2, in seconds.
+    pub fn between_parts(&self, day: u8) -> Option<u64> {
+        let first = self.stars.get(&(day, 1))?;
+        let second = self.stars.get(&(day, 2))?;
+        Some(second.time.saturating_sub(first.time))
+    }
+}
+
+/// A private leaderboard, as exported by the site's API link.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Leaderboard {
+    pub year: u16,
+    pub members: Vec<Member>,
+}
+
+impl Leaderboard {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let json = Value::parse(s)?;
+        let year = json
+            .get("event")
+            .and_then(Value::as_str)
+            .and_then(|e| e.parse().ok())
+            .ok_or("expected the year in event")?;
+        let entries = json
+            .get("members")
+            .and_then(Value::as_object)
+            .ok_or("expected an object of members")?;
+
+        let mut members = Vec::new();
+        for (key, member) in entries {
+            let invalid = || format!("member {} is invalid", key);
+            let id = member
+                .get("id")
+                .and_then(Value::as_u64)
+                .ok_or_else(invalid)?;
+            let name = match member.get("name").and_then(Value::as_str) {
+                Some(name) => name.to_owned(),
+                None => format!("(anonymous user #{})", id),
+            };
+
+            let mut stars = BTreeMap::new();
+            let days = member
+                .get("completion_day_level")
+                .and_then(Value::as_object)
+                .ok_or_else(invalid)?;
+            for (day, parts) in days {
+                let day = day.parse().map_err(|_| invalid())?;
+                for (part, star) in parts.as_object().ok_or_else(invalid)? {
+                    let star = (|| {
+                        Some(Star {
+                            time: star.get("get_star_ts")?.as_u64()?,
+                            index: star.get("star_index").and_then(Value::as_u64).unwrap_or(0),
+                        })
+                    })();
+                    let part = part.parse().map_err(|_| invalid())?;
+                    stars.insert((day, part), star.ok_or_else(invalid)?);
+                }
+            }
+            members.push(Member { id, name, stars });
+        }
+        members.sort_by_key(|m| m.id);
+        Ok(Self { year, members })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let s = fs::read_to_string(path)?;
+        Self::parse(&s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The members who got a star, fastest first, with the local score it earned them: one
+    /// point for each member there is, less one for each member who got it sooner.
+    pub fn ranking(&self, day: u8, part: u8) -> Vec<(&Member, u64)> {
+        let mut finishers = self
+            .members
+            .iter()
+            .filter_map(|m| Some((m, *m.stars.get(&(day, part))?)))
+            .collect::<Vec<_>>();
+        finishers.sort_by_key(|&(_, star)| star);
+        let count = self.members.len() as u64;
+        finishers
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (member, _))| (member, count - rank as u64))
+            .collect()
+    }
+
+    /// Every member's local score and stars, best first. Ties go to whoever got their last
+    /// star sooner, as on the site.
+    pub fn standings(&self) -> Vec<Standing<'_>> {
+        let mut scores = BTreeMap::new();
+        for day in 1..=days_in(self.year) {
+            for part in 1..=2 {
+                for (member, points) in self.ranking(day, part) {
+                    *scores.entry(member.id).or_insert(0) += points;
+                }
+            }
+        }
+        let mut standings = self
+            .members
+            .iter()
+            .map(|member| Standing {
+                member,
+                score: scores.get(&member.id).copied().unwrap_or(0),
+                stars: member.stars.len(),
+            })
+            .collect::<Vec<_>>();
+        let last_star = |s: &Standing| s.member.stars.values().map(|star| star.time).max();
+        standings.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| last_star(a).cmp(&last_star(b)))
+        });
+        standings
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Standing<'a> {
+    pub member: &'a Member,
+    pub score: u64,
+    pub stars: usize,
+}
+
+/// Shows a number of seconds as hours, minutes and seconds, like `27:03:09`.
+pub fn format_time(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// The overall standings, with each member's stars as a calendar.
+pub struct StandingsTable<'a>(pub &'a Leaderboard);
+
+impl Display for StandingsTable<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let days = days_in(self.0.year);
+        write!(f, "rank  score  ")?;
+        for day in 1..=days {
+            write!(f, "{}", day % 10)?;
+        }
+        write!(f, "  name")?;
+        for (rank, standing) in self.0.standings().iter().enumerate() {
+            write!(f, "\n{:>4}  {:>5}  ", rank + 1, standing.score)?;
+            for day in 1..=days {
+                let stars = &standing.member.stars;
+                let cell = match (stars.contains_key(&(day, 1)), stars.contains_key(&(day, 2))) {
+                    (true, true) => '*',
+                    (true, false) => '+',
+                    _ => '.',
+                };
+                write!(f, "{}", cell)?;
+            }
+            write!(f, "  {}", standing.member.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// How everyone did on one day: when they finished each part after it unlocked, the time
+/// between parts, and the points they earned.
+pub struct DayTable<'a>(pub &'a Leaderboard, pub u8);
+
+impl Display for DayTable<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let DayTable(leaderboard, day) = *self;
+        let points = |part| {
+            leaderboard
+                .ranking(day, part)
+                .into_iter()
+                .map(|(member, points)| (member.id, points))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let (points1, points2) = (points(1), points(2));
+
+        let mut members = leaderboard
+            .members
+            .iter()
+            .filter(|m| m.stars.contains_key(&(day, 1)))
+            .collect::<Vec<_>>();
+        members.sort_by_key(|m| {
+            (
+                !m.stars.contains_key(&(day, 2)),
+                m.stars.get(&(day, 2)),
+                m.stars[&(day, 1)],
+            )
+        });
+
+        write!(
+            f,
+            "{} day {}\n     part 1      part 2     between  points  name",
+            leaderboard.year, day
+        )?;
+        let time = |t: Option<u64>| t.map_or(String::new(), format_time);
+        for member in &members {
+            let score =
+                points1.get(&member.id).unwrap_or(&0) + points2.get(&member.id).unwrap_or(&0);
+            write!(
+                f,
+                "\n{:>11} {:>11} {:>11}  {:>6}  {}",
+                time(member.completion(leaderboard.year, day, 1)),
+                time(member.completion(leaderboard.year, day, 2)),
+                time(member.between_parts(day)),
+                score,
+                member.name
+            )?;
+        }
+        if members.is_empty() {
+            write!(f, "\nno one has a star yet")?;
+        }
+        Ok(())
+    }
+}
+
+/// Shows the standings of a saved leaderboard, or how each member did on `day`.
+pub fn run(path: &Path, day: Option<u8>) -> Result<(), CommandError> {
+    let leaderboard = Leaderboard::load(path)?;
+    match day {
+        Some(day) => println!("{}", DayTable(&leaderboard, day)),
+        None => println!("{}", StandingsTable(&leaderboard)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2022 day 1 unlocked at 1669870800
+    const EXPORT: &str = r#"{"event":"2022","owner_id":1,"members":{
+        "1":{"id":1,"name":"ada","stars":4,"local_score":0,"last_star_ts":1669958000,
+             "completion_day_level":{
+                "1":{"1":{"get_star_ts":1669871100,"star_index":10},"2":{"get_star_ts":1669871400,"star_index":20}},
+                "2":{"1":{"get_star_ts":1669958000,"star_index":30}}}},
+        "2":{"id":2,"name":null,"stars":2,"local_score":0,"last_star_ts":1669871000,
+             "completion_day_level":{
+                "1":{"1":{"get_star_ts":1669871000,"star_index":5},"2":{"get_star_ts":1669874400,"star_index":40}}}},
+        "3":{"id":3,"name":"grace","stars":0,"local_score":0,"last_star_ts":0,"completion_day_level":{}}}}"#;
+
+    #[test]
+    fn test_parse() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(leaderboard.year, 2022);
+        assert_eq!(leaderboard.members.len(), 3);
+        let anonymous = &leaderboard.members[1];
+        assert_eq!(anonymous.name, "(anonymous user #2)");
+        assert_eq!(anonymous.stars.len(), 2);
+        assert_eq!(anonymous.completion(2022, 1, 1), Some(200));
+        assert_eq!(anonymous.between_parts(1), Some(3400));
+        assert_eq!(leaderboard.members[0].between_parts(2), None);
+
+        assert_eq!(
+            Leaderboard::parse(r#"{"event":"2022","members":{"1":{"id":1}}}"#),
+            Err("member 1 is invalid".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_standings() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        let ranking = leaderboard
+            .ranking(1, 1)
+            .into_iter()
+            .map(|(m, points)| (m.id, points))
+            .collect::<Vec<_>>();
+        assert_eq!(ranking, [(2, 3), (1, 2)]);
+
+        // ada: 2 + 3 + 3, the anonymous user: 3 + 2
+        let standings = leaderboard
+            .standings()
+            .iter()
+            .map(|s| (s.member.id, s.score, s.stars))
+            .collect::<Vec<_>>();
+        assert_eq!(standings, [(1, 8, 3), (2, 5, 2), (3, 0, 0)]);
+
+        let table = StandingsTable(&leaderboard).to_string();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "rank  score  1234567890123456789012345  name");
+        assert_eq!(lines[1], "   1      8  *+.......................  ada");
+        assert_eq!(lines[3], "   3      0  .........................  grace");
+    }
+
+    #[test]
+    fn test_day_table() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        let expected = "\
+2022 day 1
+     part 1      part 2     between  points  name
+    0:05:00     0:10:00     0:05:00       5  ada
+    0:03:20     1:00:00     0:56:40       5  (anonymous user #2)";
+        assert_eq!(DayTable(&leaderboard, 1).to_string(), expected);
+        assert!(DayTable(&leaderboard, 9)
+            .to_string()
+            .ends_with("no one has a star yet"));
+        assert_eq!(format_time(97_389), "27:03:09");
+    }
+}
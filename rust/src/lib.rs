@@ -1,3 +1,29 @@
+pub mod anonymise;
+pub mod bench;
+pub mod cli;
+pub mod command;
+pub mod download;
+pub mod error;
+pub mod examples;
+pub mod http;
+pub mod input;
+pub mod json;
+pub mod leaderboard;
+pub mod output;
+pub mod prelude;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+#[cfg(test)]
+mod scratch;
+pub mod serve;
+pub mod session;
+pub mod solution;
+pub mod status;
+pub mod submit;
+pub mod verify;
+pub mod watch;
 pub mod year2019;
+pub mod year2021;
 pub mod year2022;
 pub mod year2024;
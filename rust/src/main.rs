@@ -1,3 +1,18 @@
+use aoc::cli::USAGE;
+use aoc::command::CommandError;
+
 fn main() {
-    aoc::year2024::day01::run_part2();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match aoc::cli::main(&args) {
+        Ok(()) => {}
+        Err(CommandError::Failed) => std::process::exit(1),
+        Err(CommandError::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    }
 }
@@ -0,0 +1,331 @@
+use crate::input::Inputs;
+use crate::json::Value;
+use crate::runner::Job;
+use std::any::Any;
+use std::io::{self, Write};
+use std::panic;
+use std::time::{Duration, Instant};
+
+/// Bumped whenever a field of the JSON or CSV output changes meaning or goes away.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Status {
+    Ok,
+    /// The input couldn't be read.
+    NoInput,
+    /// The input was read, but the day couldn't parse it; the error says where.
+    InvalidInput,
+    /// The solution panicked.
+    Panic,
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::NoInput => "no_input",
+            Status::InvalidInput => "invalid_input",
+            Status::Panic => "panic",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ok" => Some(Status::Ok),
+            "no_input" => Some(Status::NoInput),
+            "invalid_input" => Some(Status::InvalidInput),
+            "panic" => Some(Status::Panic),
+            _ => None,
+        }
+    }
+}
+
+/// The result of running one part of one day.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub duration: Duration,
+    pub status: Status,
+    pub error: Option<String>,
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "the solution panicked".to_owned(),
+        },
+    }
+}
+
+impl Record {
+    /// Reads the job's input and times its solution, catching a parse error or a panic as an
+    /// error record.
+    pub fn run(job: &Job, inputs: &Inputs) -> Self {
+        match inputs.read(job.year, job.day) {
+            Ok(data) => Self::solve(job, &data),
+            Err(e) => Record {
+                year: job.year,
+                day: job.day,
+                part: job.part,
+                answer: None,
+                duration: Duration::ZERO,
+                status: Status::NoInput,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Times the job's solution on `data`, catching a parse error or a panic as an error record.
+    pub fn solve(job: &Job, data: &str) -> Self {
+        let mut record = Record {
+            year: job.year,
+            day: job.day,
+            part: job.part,
+            answer: None,
+            duration: Duration::ZERO,
+            status: Status::Ok,
+            error: None,
+        };
+        let start = Instant::now();
+        let answer = panic::catch_unwind(|| (job.solve)(data));
+        record.duration = start.elapsed();
+        match answer {
+            Ok(Ok(answer)) => record.answer = Some(answer),
+            Ok(Err(e)) => {
+                record.status = Status::InvalidInput;
+                record.error = Some(e.to_string());
+            }
+            Err(payload) => {
+                record.status = Status::Panic;
+                record.error = Some(panic_message(payload.as_ref()));
+            }
+        }
+        record
+    }
+
+    fn to_json(&self) -> Value {
+        Value::object([
+            ("year", Value::from(self.year)),
+            ("day", Value::from(self.day)),
+            ("part", Value::from(self.part)),
+            ("answer", Value::from(self.answer.clone())),
+            ("duration_ns", Value::from(self.duration.as_nanos() as u64)),
+            ("status", Value::from(self.status.name())),
+            ("error", Value::from(self.error.clone())),
+        ])
+    }
+
+    /// Reads back the records of a JSON document written by `Output`.
+    pub fn from_json(value: &Value) -> Result<Vec<Record>, String> {
+        let results = value
+            .get("results")
+            .and_then(Value::as_array)
+            .ok_or("expected an object with a results array")?;
+        results
+            .iter()
+            .enumerate()
+            .map(|(i, result)| {
+                let number = |key| result.get(key).and_then(Value::as_u64);
+                let text = |key| result.get(key).and_then(Value::as_str).map(str::to_owned);
+                let record = (|| {
+                    Some(Record {
+                        year: number("year")?.try_into().ok()?,
+                        day: number("day")?.try_into().ok()?,
+                        part: number("part")?.try_into().ok()?,
+                        answer: text("answer"),
+                        duration: Duration::from_nanos(number("duration_ns")?),
+                        status: Status::from_name(result.get("status")?.as_str()?)?,
+                        error: text("error"),
+                    })
+                })();
+                record.ok_or_else(|| format!("result {} is invalid", i))
+            })
+            .collect()
+    }
+
+    fn to_csv(&self) -> String {
+        let fields = [
+            self.year.to_string(),
+            self.day.to_string(),
+            self.part.to_string(),
+            csv_field(self.answer.as_deref().unwrap_or("")),
+            self.duration.as_nanos().to_string(),
+            self.status.name().to_owned(),
+            csv_field(self.error.as_deref().unwrap_or("")),
+        ];
+        fields.join(",")
+    }
+}
+
+const CSV_HEADER: &str = "year,day,part,answer,duration_ns,status,error";
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+/// Writes records as they come in. Text and CSV are written a line at a time so long runs show
+/// progress; JSON is one document, written by `finish`.
+///
+/// The JSON is `{"version":1,"results":[…]}` with a record per part; CSV has a header line
+/// and the same fields. `answer` and `error` are null, or empty in CSV, when there isn't one.
+pub struct Output<W: Write> {
+    format: Format,
+    out: W,
+    records: Vec<Value>,
+}
+
+impl<W: Write> Output<W> {
+    pub fn new(format: Format, mut out: W) -> io::Result<Self> {
+        if format == Format::Csv {
+            writeln!(out, "{}", CSV_HEADER)?;
+        }
+        Ok(Self {
+            format,
+            out,
+            records: Vec::new(),
+        })
+    }
+
+    pub fn push(&mut self, record: &Record) -> io::Result<()> {
+        match self.format {
+            Format::Text => {
+                let result = record.answer.as_ref().or(record.error.as_ref());
+                writeln!(
+                    self.out,
+                    "{} day {} part {}: {}",
+                    record.year,
+                    record.day,
+                    record.part,
+                    result.map_or("", |r| r.as_str())
+                )
+            }
+            Format::Csv => writeln!(self.out, "{}", record.to_csv()),
+            Format::Json => {
+                self.records.push(record.to_json());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.format == Format::Json {
+            let document = Value::object([
+                ("version", Value::from(SCHEMA_VERSION)),
+                ("results", Value::Array(self.records)),
+            ]);
+            writeln!(self.out, "{}", document)?;
+        }
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::DEFAULT_DATA_DIR;
+    use crate::year2019::day01::Day01;
+
+    fn records() -> Vec<Record> {
+        let record = |part, answer: Option<&str>, status, error: Option<&str>| Record {
+            year: 2019,
+            day: 4,
+            part,
+            answer: answer.map(|a| a.to_owned()),
+            duration: Duration::from_micros(1500),
+            status,
+            error: error.map(|e| e.to_owned()),
+        };
+        vec![
+            record(1, Some("1890"), Status::Ok, None),
+            record(2, None, Status::Panic, Some("bad range \"1,2\"")),
+        ]
+    }
+
+    fn render(format: Format) -> String {
+        let mut output = Output::new(format, Vec::new()).unwrap();
+        for record in records() {
+            output.push(&record).unwrap();
+        }
+        String::from_utf8(output.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(
+            render(Format::Text),
+            "2019 day 4 part 1: 1890\n2019 day 4 part 2: bad range \"1,2\"\n"
+        );
+        assert_eq!(
+            render(Format::Csv),
+            "year,day,part,answer,duration_ns,status,error\n\
+             2019,4,1,1890,1500000,ok,\n\
+             2019,4,2,,1500000,panic,\"bad range \"\"1,2\"\"\"\n"
+        );
+        assert_eq!(
+            render(Format::Json),
+            r#"{"version":1,"results":[{"year":2019,"day":4,"part":1,"answer":"1890","duration_ns":1500000,"status":"ok","error":null},{"year":2019,"day":4,"part":2,"answer":null,"duration_ns":1500000,"status":"panic","error":"bad range \"1,2\""}]}"#
+                .to_owned()
+                + "\n"
+        );
+        let json = Value::parse(&render(Format::Json)).unwrap();
+        assert_eq!(Record::from_json(&json).unwrap(), records());
+    }
+
+    #[test]
+    fn test_run() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        let job = |day, solve| Job {
+            year: 2019,
+            day,
+            part: 1,
+            solve,
+        };
+
+        let record = Record::run(&job(4, |data| Ok(data.trim().to_owned())), &inputs);
+        assert_eq!(record.status, Status::Ok);
+        assert_eq!(record.answer.as_deref(), Some("138241-674034"));
+
+        let record = Record::run(&job(4, |_| panic!("no {}", "range")), &inputs);
+        assert_eq!(record.status, Status::Panic);
+        assert_eq!(record.error.as_deref(), Some("no range"));
+
+        let record = Record::run(&job(4, crate::solution::solve_part1::<Day01>), &inputs);
+        assert_eq!(record.status, Status::InvalidInput);
+        assert_eq!(
+            record.error.as_deref(),
+            Some("line 1, column 1: expected a number, found \"138241-674034\"")
+        );
+
+        let record = Record::run(&job(24, |data| Ok(data.to_owned())), &inputs);
+        assert_eq!(record.status, Status::NoInput);
+        assert!(record.error.unwrap().contains("day24.txt"));
+    }
+}
@@ -0,0 +1,20 @@
+//! The traits and types for using the solutions from another crate, with
+//! `use aoc::prelude::*;`. Each day's solution is a `DayNN` type in its year's module, which
+//! parses its input with [`Solution::parse`] and answers each part from the parsed input;
+//! [`DAYS`] has every one of them behind functions that take the raw input.
+//!
+//! ```
+//! use aoc::prelude::*;
+//! use aoc::year2022::day01::Day01;
+//!
+//! let elves = Day01::parse("1000\n\n2000")?;
+//! assert_eq!(Day01::part1(&elves), 2000);
+//!
+//! let day = DAYS.iter().find(|d| (d.year, d.day) == (2022, 1)).unwrap();
+//! assert_eq!(day.part1.unwrap()("1000\n\n2000")?, "2000");
+//! # Ok::<(), ParseError>(())
+//! ```
+
+pub use crate::error::ParseError;
+pub use crate::registry::{Day, DAYS};
+pub use crate::solution::{solve_part1, solve_part2, Solution};
@@ -0,0 +1,206 @@
+use crate::anonymise;
+use crate::bench::{self, Measure};
+use crate::error::ParseError;
+use crate::solution::{solve_part1, solve_part2, Solution};
+use crate::{year2019, year2021, year2022, year2024};
+use std::fmt::{Display, Formatter};
+
+/// Answers one part of a day from its raw input.
+pub type Part = fn(&str) -> Result<String, ParseError>;
+
+/// Makes up an input shaped like the given one from a seed.
+pub type Synthesise = fn(&str, u64) -> Result<String, &'static str>;
+
+/// The solutions for one day of one year.
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub part1: Option<Part>,
+    pub part2: Option<Part>,
+    pub bench: Measure,
+    pub check: fn(&str) -> Result<(), ParseError>,
+    pub synthesise: Synthesise,
+}
+
+impl Day {
+    pub fn part(&self, part: u8) -> Option<Part> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+
+    /// The parts that have solutions.
+    pub fn parts(&self) -> Vec<u8> {
+        (1..=2).filter(|&part| self.part(part).is_some()).collect()
+    }
+}
+
+const fn day<S: Solution>(year: u16, day: u8) -> Day {
+    Day {
+        year,
+        day,
+        part1: Some(solve_part1::<S>),
+        part2: Some(solve_part2::<S>),
+        bench: bench::measure::<S>,
+        check: anonymise::check::<S>,
+        synthesise: anonymise::synthesise_with::<S>,
+    }
+}
+
+/// Every solution, in order of year and day.
+pub const DAYS: &[Day] = &[
+    day::<year2019::day01::Day01>(2019, 1),
+    day::<year2019::day02::Day02>(2019, 2),
+    day::<year2019::day03::Day03>(2019, 3),
+    day::<year2019::day04::Day04>(2019, 4),
+    day::<year2019::day05::Day05>(2019, 5),
+    day::<year2019::day06::Day06>(2019, 6),
+    Day {
+        part2: None,
+        ..day::<year2019::day25::Day25>(2019, 25)
+    },
+    day::<year2021::day01::Day01>(2021, 1),
+    day::<year2021::day02::Day02>(2021, 2),
+    day::<year2022::day01::Day01>(2022, 1),
+    day::<year2022::day02::Day02>(2022, 2),
+    day::<year2022::day03::Day03>(2022, 3),
+    day::<year2022::day04::Day04>(2022, 4),
+    day::<year2024::day01::Day01>(2024, 1),
+];
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LookupError {
+    UnknownYear { year: u16, years: Vec<u16> },
+    UnknownDay { year: u16, day: u8, days: Vec<u8> },
+    UnimplementedPart { year: u16, day: u8, part: u8 },
+}
+
+impl Display for LookupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let list = |items: Vec<String>| items.join(", ");
+        match self {
+            LookupError::UnknownYear { year, years } => write!(
+                f,
+                "there are no solutions for {}; try one of {}",
+                year,
+                list(years.iter().map(|y| y.to_string()).collect())
+            ),
+            LookupError::UnknownDay { year, day, days } => write!(
+                f,
+                "day {} of {} hasn't been solved; try one of {}",
+                day,
+                year,
+                list(days.iter().map(|d| d.to_string()).collect())
+            ),
+            LookupError::UnimplementedPart { year, day, part } => {
+                write!(
+                    f,
+                    "part {} of {} day {} hasn't been solved",
+                    part, year, day
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LookupError {}
+
+pub fn years() -> Vec<u16> {
+    let mut years = DAYS.iter().map(|d| d.year).collect::<Vec<_>>();
+    years.dedup();
+    years
+}
+
+/// All the days solved in `year`.
+pub fn year(year: u16) -> Result<Vec<&'static Day>, LookupError> {
+    let days = DAYS.iter().filter(|d| d.year == year).collect::<Vec<_>>();
+    if days.is_empty() {
+        Err(LookupError::UnknownYear {
+            year,
+            years: years(),
+        })
+    } else {
+        Ok(days)
+    }
+}
+
+pub fn find(year: u16, day: u8) -> Result<&'static Day, LookupError> {
+    let days = self::year(year)?;
+    days.iter()
+        .find(|d| d.day == day)
+        .copied()
+        .ok_or_else(|| LookupError::UnknownDay {
+            year,
+            day,
+            days: days.iter().map(|d| d.day).collect(),
+        })
+}
+
+pub fn find_part(year: u16, day: u8, part: u8) -> Result<Part, LookupError> {
+    find(year, day)?
+        .part(part)
+        .ok_or(LookupError::UnimplementedPart { year, day, part })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_sorted() {
+        let keys = DAYS.iter().map(|d| (d.year, d.day)).collect::<Vec<_>>();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(keys, sorted);
+    }
+
+    #[test]
+    fn test_find() {
+        let day = find(2019, 3).unwrap();
+        assert_eq!((day.year, day.day), (2019, 3));
+        assert!(find_part(2022, 4, 2).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_year() {
+        let error = find(2020, 1).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "there are no solutions for 2020; try one of 2019, 2021, 2022, 2024"
+        );
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        let error = find(2024, 2).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "day 2 of 2024 hasn't been solved; try one of 1"
+        );
+    }
+
+    #[test]
+    fn test_unimplemented_part() {
+        let error = find_part(2019, 25, 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "part 2 of 2019 day 25 hasn't been solved"
+        );
+        assert!(find_part(2019, 1, 3).is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        let day = find(2022, 1).unwrap();
+        let data = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+        assert_eq!(day.part1.unwrap()(data), Ok("24000".to_owned()));
+        assert_eq!(day.part2.unwrap()(data), Ok("45000".to_owned()));
+        assert_eq!(
+            day.part1.unwrap()("1000\n2OOO").unwrap_err().to_string(),
+            "line 2, column 1: expected a number, found \"2OOO\""
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use crate::bench::format_duration;
+use crate::command::CommandError;
+use crate::download;
+use crate::input::Inputs;
+use crate::output::{Format, Output, Record, Status};
+use crate::registry::{self, Day, LookupError, Part};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Which solutions to run.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Selection {
+    Year(u16),
+    Day(u16, u8),
+    Part(u16, u8, u8),
+}
+
+/// One part of one day, ready to run.
+#[derive(Debug, Clone, Copy)]
+pub struct Job {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub solve: Part,
+}
+
+/// The solutions a selection refers to, in order.
+pub fn resolve(selection: Selection) -> Result<Vec<Job>, LookupError> {
+    let parts = |day: &Day| {
+        (1..=2)
+            .filter_map(|part| {
+                day.part(part).map(|solve| Job {
+                    year: day.year,
+                    day: day.day,
+                    part,
+                    solve,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    Ok(match selection {
+        Selection::Year(year) => registry::year(year)?.into_iter().flat_map(parts).collect(),
+        Selection::Day(year, day) => parts(registry::find(year, day)?),
+        Selection::Part(year, day, part) => vec![Job {
+            year,
+            day,
+            part,
+            solve: registry::find_part(year, day, part)?,
+        }],
+    })
+}
+
+/// Every part of every day.
+pub fn resolve_all() -> Vec<Job> {
+    registry::DAYS
+        .iter()
+        .flat_map(|day| {
+            day.parts().into_iter().map(|part| Job {
+                year: day.year,
+                day: day.day,
+                part,
+                solve: day.part(part).unwrap(),
+            })
+        })
+        .collect()
+}
+
+/// What a run came to.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Summary {
+    pub days: usize,
+    pub parts: usize,
+    pub ok: usize,
+    /// The parts that didn't give an answer, and why.
+    pub failures: Vec<String>,
+    /// The parts of days whose input hasn't been saved, which aren't failures: a fresh checkout
+    /// only has the inputs that were committed.
+    pub missing: Vec<String>,
+    /// The time spent solving, added up over every part.
+    pub solving: Duration,
+    /// How long the whole run took.
+    pub elapsed: Duration,
+}
+
+impl Summary {
+    fn add(&mut self, record: &Record) {
+        self.parts += 1;
+        self.solving += record.duration;
+        let mut line = format!(
+            "{} day {} part {}: {}",
+            record.year,
+            record.day,
+            record.part,
+            record.status.name()
+        );
+        match record.status {
+            Status::Ok => self.ok += 1,
+            Status::NoInput => self.missing.push(line),
+            status => {
+                if let (Status::InvalidInput, Some(error)) = (status, &record.error) {
+                    line.push_str(&format!(": {}", error));
+                }
+                self.failures.push(line);
+            }
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} days, {} parts in {} ({} solving): {} ok, {} failed, {} without input",
+            self.days,
+            self.parts,
+            format_duration(self.elapsed),
+            format_duration(self.solving),
+            self.ok,
+            self.failures.len(),
+            self.missing.len()
+        )?;
+        for failure in self.failures.iter().chain(&self.missing) {
+            write!(f, "\n  {}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+/// How many days to run at once when nothing says otherwise.
+pub fn default_workers() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Runs `jobs` a day at a time on `workers` threads, handing each record to `emit` in the order
+/// of `jobs`. A day that panics only fails its own parts.
+pub fn run_jobs(
+    jobs: &[Job],
+    inputs: &Inputs,
+    workers: usize,
+    mut emit: impl FnMut(&Record) -> io::Result<()>,
+) -> io::Result<Summary> {
+    let start = Instant::now();
+    let days = jobs
+        .chunk_by(|a, b| (a.year, a.day) == (b.year, b.day))
+        .collect::<Vec<_>>();
+    let mut summary = Summary {
+        days: days.len(),
+        ..Summary::default()
+    };
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, days.len().max(1)) {
+            let (sender, next, days) = (sender.clone(), &next, &days);
+            scope.spawn(move || {
+                while let Some(day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let records = day
+                        .iter()
+                        .map(|job| Record::run(job, inputs))
+                        .collect::<Vec<_>>();
+                    if sender.send((day[0].year, day[0].day, records)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // days finish out of order, so hold on to them until the ones before have been emitted
+        let mut order = days.iter().map(|day| (day[0].year, day[0].day));
+        let mut waiting = order.next();
+        let mut finished = BTreeMap::new();
+        for (year, day, records) in receiver {
+            finished.insert((year, day), records);
+            while let Some(records) = waiting.and_then(|key| finished.remove(&key)) {
+                for record in &records {
+                    summary.add(record);
+                    emit(record)?;
+                }
+                waiting = order.next();
+            }
+        }
+        Ok::<_, io::Error>(())
+    })?;
+
+    summary.elapsed = start.elapsed();
+    Ok(summary)
+}
+
+/// Runs `jobs` on `workers` threads, downloading any inputs that are missing first and writing
+/// each record to stdout in `format` as soon as it's ready.
+fn emit(jobs: &[Job], inputs: &Inputs, workers: usize, format: Format) -> io::Result<Summary> {
+    download::fetch_missing(inputs, jobs);
+    // panics end up in the records, so the default hook's report would only repeat them
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let summary = Output::new(format, io::stdout().lock()).and_then(|mut output| {
+        let summary = run_jobs(jobs, inputs, workers, |record| output.push(record))?;
+        output.finish()?.flush()?;
+        Ok(summary)
+    });
+    panic::set_hook(hook);
+    summary
+}
+
+/// Runs `jobs` in order, failing unless every part gave an answer.
+pub fn run(jobs: &[Job], inputs: &Inputs, format: Format) -> Result<(), CommandError> {
+    let summary = emit(jobs, inputs, 1, format)?;
+    if !summary.failures.is_empty() || !summary.missing.is_empty() {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+/// Runs every part of every day on `workers` threads and ends with a summary. Days without an
+/// input are listed but don't fail the run.
+pub fn run_all(inputs: &Inputs, workers: usize, format: Format) -> Result<(), CommandError> {
+    let summary = emit(&resolve_all(), inputs, workers, format)?;
+    // keep json and csv on stdout parseable
+    match format {
+        Format::Text => println!("\n{}", summary),
+        _ => eprintln!("{}", summary),
+    }
+    if !summary.failures.is_empty() {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::DEFAULT_DATA_DIR;
+
+    #[test]
+    fn test_run() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        let job = |year, day, part, solve| Job {
+            year,
+            day,
+            part,
+            solve,
+        };
+        let jobs = [
+            job(2019, 4, 1, |_| {
+                std::thread::sleep(Duration::from_millis(50));
+                Ok("slow".to_owned())
+            }),
+            job(2019, 4, 2, |_| panic!("bad input")),
+            job(2022, 1, 1, |data| Ok(data.lines().count().to_string())),
+            job(2024, 7, 1, |_| Ok("unreachable".to_owned())),
+        ];
+
+        let mut emitted = Vec::new();
+        let summary = run_jobs(&jobs, &inputs, 4, |record| {
+            emitted.push((record.year, record.day, record.part, record.status));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            emitted,
+            [
+                (2019, 4, 1, Status::Ok),
+                (2019, 4, 2, Status::Panic),
+                (2022, 1, 1, Status::Ok),
+                (2024, 7, 1, Status::NoInput),
+            ]
+        );
+        assert_eq!((summary.days, summary.parts, summary.ok), (3, 4, 2));
+        assert_eq!(summary.failures, ["2019 day 4 part 2: panic"]);
+        assert_eq!(summary.missing, ["2024 day 7 part 1: no_input"]);
+        assert!(summary.to_string().starts_with("3 days, 4 parts in "));
+        assert!(summary.to_string().ends_with(
+            "2 ok, 1 failed, 1 without input\n  2019 day 4 part 2: panic\n  2024 day 7 part 1: no_input"
+        ));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let parts = |selection| {
+            resolve(selection)
+                .unwrap()
+                .into_iter()
+                .map(|job| (job.year, job.day, job.part))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parts(Selection::Day(2019, 25)), [(2019, 25, 1)]);
+        assert_eq!(parts(Selection::Year(2024)), [(2024, 1, 1), (2024, 1, 2)]);
+        assert!(matches!(
+            resolve(Selection::Part(2019, 25, 2)),
+            Err(LookupError::UnimplementedPart { .. })
+        ));
+    }
+}
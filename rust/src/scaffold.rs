@@ -0,0 +1,398 @@
+use crate::command::CommandError;
+use crate::download::days_in;
+use crate::input::Inputs;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The crate new days are written into.
+pub const CRATE_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    NoSuchDay {
+        year: u16,
+        day: u8,
+    },
+    Exists(PathBuf),
+    Registered {
+        year: u16,
+        day: u8,
+    },
+    /// A file that needs a new line isn't laid out the way the generator expects.
+    Unrecognised {
+        path: PathBuf,
+        expected: String,
+    },
+    Io(io::Error),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaffoldError::NoSuchDay { year, day } => {
+                write!(f, "there is no day {} in {}", day, year)
+            }
+            ScaffoldError::Exists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Registered { year, day } => {
+                write!(f, "day {} of {} is already registered", day, year)
+            }
+            ScaffoldError::Unrecognised { path, expected } => write!(
+                f,
+                "could not find {} in {}; add the day by hand",
+                expected,
+                path.display()
+            ),
+            ScaffoldError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(e: io::Error) -> Self {
+        ScaffoldError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Change {
+    Created(PathBuf),
+    Updated(PathBuf),
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Created(path) => write!(f, "created {}", path.display()),
+            Change::Updated(path) => write!(f, "updated {}", path.display()),
+        }
+    }
+}
+
+/// The skeleton every day starts from.
+pub fn template(day: u8) -> String {
+    let name = format!("Day{:02}", day);
+    format!(
+        r#"use crate::error::ParseError;
+use crate::solution::Solution;
+
+pub struct {name};
+
+impl Solution for {name} {{
+    type Input<'a> = Vec<&'a str>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {{
+        parse_data(data)
+    }}
+
+    fn part1(lines: &Self::Input<'_>) -> usize {{
+        todo!("part 1 for {{}} lines", lines.len())
+    }}
+
+    fn part2(lines: &Self::Input<'_>) -> usize {{
+        todo!("part 2 for {{}} lines", lines.len())
+    }}
+}}
+
+fn parse_data(data: &str) -> Result<Vec<&str>, ParseError> {{
+    Ok(data.lines().collect())
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const TEST_DATA: &str = "\
+";
+
+    #[test]
+    fn test_part1() {{
+        let lines = parse_data(TEST_DATA).unwrap();
+        assert_eq!({name}::part1(&lines), 0);
+    }}
+
+    #[test]
+    fn test_part2() {{
+        let lines = parse_data(TEST_DATA).unwrap();
+        assert_eq!({name}::part2(&lines), 0);
+    }}
+}}
+"#
+    )
+}
+
+/// Adds `pub mod name;` to a list of modules, keeping it sorted. Returns `None` if it's
+/// already there.
+fn add_module(text: &str, name: &str) -> Option<String> {
+    let line = format!("pub mod {};", name);
+    let mut lines = text.lines().collect::<Vec<_>>();
+    if lines.contains(&line.as_str()) {
+        return None;
+    }
+    let modules = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| Some((i, l.strip_prefix("pub mod ")?)))
+        .collect::<Vec<_>>();
+    let index = match modules
+        .iter()
+        .find(|(_, module)| module.trim_end_matches(';') > name)
+    {
+        Some(&(i, _)) => i,
+        None => modules.last().map_or(lines.len(), |&(i, _)| i + 1),
+    };
+    lines.insert(index, &line);
+    Some(lines.join("\n") + "\n")
+}
+
+/// The year and day a line of `DAYS` registers, like `day::<…>(2019, 4),`.
+fn entry_key(line: &str) -> Option<(u16, u8)> {
+    let (_, args) = line.rsplit_once(">(")?;
+    let (year, day) = args.strip_suffix("),").unwrap_or(args).split_once(", ")?;
+    Some((year.parse().ok()?, day.trim_end_matches(')').parse().ok()?))
+}
+
+/// Adds the day to the year imports and to `DAYS` in the registry at `path`, in order.
+fn register(text: &str, path: &Path, year: u16, day: u8) -> Result<String, ScaffoldError> {
+    let unrecognised = |expected: &str| ScaffoldError::Unrecognised {
+        path: path.to_owned(),
+        expected: expected.to_owned(),
+    };
+    let mut lines = text.lines().map(str::to_owned).collect::<Vec<_>>();
+    let module = format!("year{}", year);
+
+    let imports = lines
+        .iter()
+        .position(|l| l.starts_with("use crate::{year"))
+        .ok_or_else(|| unrecognised("the year imports"))?;
+    let list = lines[imports]
+        .strip_prefix("use crate::{")
+        .and_then(|l| l.strip_suffix("};"))
+        .ok_or_else(|| unrecognised("the year imports"))?;
+    let mut years = list.split(", ").collect::<Vec<_>>();
+    if !years.contains(&module.as_str()) {
+        years.push(&module);
+        years.sort();
+        lines[imports] = format!("use crate::{{{}}};", years.join(", "));
+    }
+
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("pub const DAYS"))
+        .ok_or_else(|| unrecognised("DAYS"))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|l| l == "];")
+            .ok_or_else(|| unrecognised("the end of DAYS"))?;
+    let keys = (start..end).filter_map(|i| Some((i, entry_key(lines[i].trim())?)));
+    let mut index = end;
+    for (i, key) in keys {
+        if key == (year, day) {
+            return Err(ScaffoldError::Registered { year, day });
+        }
+        if key > (year, day) {
+            index = i;
+            // an entry that overrides fields starts at its `Day {`
+            if lines[i].trim().starts_with("..") {
+                while lines[index].trim() != "Day {" {
+                    index -= 1;
+                }
+            }
+            break;
+        }
+    }
+    lines.insert(
+        index,
+        format!(
+            "    day::<{}::day{:02}::Day{:02}>({}, {}),",
+            module, day, day, year, day
+        ),
+    );
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Writes the module for a new day into the crate at `crate_dir`, registers it, and leaves an
+/// empty input at `input` to paste the puzzle input into. Creates the year's module when it's
+/// the first day of the year. Nothing is written unless every file can be updated.
+pub fn scaffold(
+    crate_dir: &Path,
+    input: &Path,
+    year: u16,
+    day: u8,
+) -> Result<Vec<Change>, ScaffoldError> {
+    if year < 2015 || day == 0 || day > days_in(year) {
+        return Err(ScaffoldError::NoSuchDay { year, day });
+    }
+
+    let src = crate_dir.join("src");
+    let year_dir = src.join(format!("year{}", year));
+    let day_file = year_dir.join(format!("day{:02}.rs", day));
+    if day_file.exists() {
+        return Err(ScaffoldError::Exists(day_file));
+    }
+
+    let read = |path: &Path| fs::read_to_string(path).map_err(ScaffoldError::from);
+
+    let mut edits = Vec::new();
+    let registry_file = src.join("registry.rs");
+    let registry = register(&read(&registry_file)?, &registry_file, year, day)?;
+    edits.push((registry_file, registry));
+
+    let mod_file = year_dir.join("mod.rs");
+    if year_dir.exists() {
+        if let Some(modules) = add_module(&read(&mod_file)?, &format!("day{:02}", day)) {
+            edits.push((mod_file.clone(), modules));
+        }
+    } else {
+        let lib_file = src.join("lib.rs");
+        if let Some(lib) = add_module(&read(&lib_file)?, &format!("year{}", year)) {
+            edits.push((lib_file, lib));
+        }
+    }
+
+    let mut changes = Vec::new();
+    if !year_dir.exists() {
+        fs::create_dir_all(&year_dir)?;
+        fs::write(&mod_file, format!("pub mod day{:02};\n", day))?;
+        changes.push(Change::Created(mod_file));
+    }
+    fs::write(&day_file, template(day))?;
+    changes.push(Change::Created(day_file));
+    for (path, text) in edits {
+        fs::write(&path, text)?;
+        changes.push(Change::Updated(path));
+    }
+    if !input.exists() {
+        if let Some(parent) = input.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(input, "")?;
+        changes.push(Change::Created(input.to_owned()));
+    }
+    Ok(changes)
+}
+
+/// Starts a day in the crate it was built from, printing every file it touched.
+pub fn run(year: u16, day: u8, inputs: &Inputs) -> Result<(), CommandError> {
+    for change in scaffold(Path::new(CRATE_DIR), &inputs.path(year, day), year, day)? {
+        println!("{}", change);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    const REGISTRY: &str = "\
+use crate::{year2019, year2024};
+
+pub const DAYS: &[Day] = &[
+    day::<year2019::day01::Day01>(2019, 1),
+    Day {
+        part2: None,
+        ..day::<year2019::day25::Day25>(2019, 25)
+    },
+    day::<year2024::day01::Day01>(2024, 1),
+];
+";
+
+    #[test]
+    fn test_add_module() {
+        let modules = "pub mod day01;\npub mod day03;\n";
+        assert_eq!(
+            add_module(modules, "day02").unwrap(),
+            "pub mod day01;\npub mod day02;\npub mod day03;\n"
+        );
+        assert_eq!(
+            add_module(modules, "day10").unwrap(),
+            "pub mod day01;\npub mod day03;\npub mod day10;\n"
+        );
+        assert_eq!(add_module(modules, "day03"), None);
+    }
+
+    #[test]
+    fn test_register() {
+        let register = |year, day| register(REGISTRY, Path::new("registry.rs"), year, day);
+        let registry = register(2019, 7).unwrap();
+        assert!(registry.contains(
+            "    day::<year2019::day01::Day01>(2019, 1),
+    day::<year2019::day07::Day07>(2019, 7),
+    Day {"
+        ));
+
+        let registry = register(2022, 1).unwrap();
+        assert!(registry.starts_with("use crate::{year2019, year2022, year2024};"));
+        assert!(registry.contains(
+            "    },
+    day::<year2022::day01::Day01>(2022, 1),
+    day::<year2024::day01::Day01>(2024, 1),"
+        ));
+
+        let registry = register(2024, 2).unwrap();
+        assert!(registry.ends_with("(2024, 1),\n    day::<year2024::day02::Day02>(2024, 2),\n];\n"));
+
+        assert!(matches!(
+            register(2019, 25),
+            Err(ScaffoldError::Registered { .. })
+        ));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let dir = Scratch::new("scaffold");
+        fs::create_dir_all(dir.join("src/year2019")).unwrap();
+        fs::write(
+            dir.join("src/lib.rs"),
+            "pub mod registry;\npub mod year2019;\npub mod year2024;\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/registry.rs"), REGISTRY).unwrap();
+        fs::write(
+            dir.join("src/year2019/mod.rs"),
+            "pub mod day01;\npub mod day25;\n",
+        )
+        .unwrap();
+        let input = dir.join("data/year2022/day01.txt");
+
+        let changes = scaffold(&dir, &input, 2022, 1).unwrap();
+        assert_eq!(
+            changes,
+            [
+                Change::Created(dir.join("src/year2022/mod.rs")),
+                Change::Created(dir.join("src/year2022/day01.rs")),
+                Change::Updated(dir.join("src/registry.rs")),
+                Change::Updated(dir.join("src/lib.rs")),
+                Change::Created(input.clone()),
+            ]
+        );
+        let read = |path: &str| fs::read_to_string(dir.join(path)).unwrap();
+        assert_eq!(
+            read("src/lib.rs"),
+            "pub mod registry;\npub mod year2019;\npub mod year2022;\npub mod year2024;\n"
+        );
+        assert_eq!(read("src/year2022/mod.rs"), "pub mod day01;\n");
+        assert!(read("src/year2022/day01.rs").contains("impl Solution for Day01 {"));
+        assert_eq!(read("data/year2022/day01.txt"), "");
+
+        scaffold(&dir, &dir.join("data/year2019/day02.txt"), 2019, 2).unwrap();
+        assert_eq!(
+            read("src/year2019/mod.rs"),
+            "pub mod day01;\npub mod day02;\npub mod day25;\n"
+        );
+
+        assert!(matches!(
+            scaffold(&dir, &input, 2022, 1),
+            Err(ScaffoldError::Exists(_))
+        ));
+        assert!(matches!(
+            scaffold(&dir, &input, 2025, 13),
+            Err(ScaffoldError::NoSuchDay { .. })
+        ));
+    }
+}
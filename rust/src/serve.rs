@@ -0,0 +1,706 @@
+use crate::bench::format_duration;
+use crate::command::CommandError;
+use crate::http::form_decode;
+use crate::input::Inputs;
+use crate::output::{Record, Status};
+use crate::registry::{self, Day, DAYS};
+use crate::runner::Job;
+use crate::status::{self, YearStatus};
+use crate::submit::{self, History};
+use crate::verify::{self, Answers};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// The port the dashboard listens on when nothing says otherwise.
+pub const DEFAULT_PORT: u16 = 8025;
+
+/// The largest request body accepted, which is far more than any puzzle input.
+const MAX_BODY: usize = 16 << 20;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a request may take before it's answered with an error. A solution can't be
+/// stopped, so it carries on in the background, and a saved input's answer is kept for the
+/// next time. Until it's done, requests to run the same part are turned away.
+pub const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Listens on `port` of the loopback interface only, so nothing off this machine can reach
+/// the dashboard. Port 0 picks a free one.
+pub fn bind(port: u16) -> io::Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// The path, without the query string.
+    pub path: String,
+    pub host: Option<String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn read(mut reader: impl BufRead) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut words = line.split_whitespace();
+        let (Some(method), Some(target)) = (words.next(), words.next()) else {
+            return Err(invalid("bad request line"));
+        };
+        let method = method.to_owned();
+        let path = target.split('?').next().unwrap_or(target).to_owned();
+
+        let mut host = None;
+        let mut length = 0;
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                match name.to_ascii_lowercase().as_str() {
+                    "host" => host = Some(value.trim().to_owned()),
+                    "content-length" => {
+                        length = value.trim().parse().map_err(|_| invalid("bad length"))?
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if length > MAX_BODY {
+            return Err(invalid("the body is too large"));
+        }
+
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8(body).map_err(|_| invalid("the body isn't utf-8"))?;
+        Ok(Self {
+            method,
+            path,
+            host,
+            body,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Reply {
+    fn html(status: u16, title: &str, content: &str) -> Self {
+        let page = format!(
+            "<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+             <style>{}</style></head>\n<body>\n<h1>{}</h1>\n{}</body></html>\n",
+            escape(title),
+            STYLE,
+            escape(title),
+            content
+        );
+        Self {
+            status,
+            content_type: "text/html; charset=utf-8",
+            body: page.into_bytes(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        let content = format!(
+            "<p>{}</p>\n<p><a href=\"/\">back</a></p>\n",
+            escape(message)
+        );
+        Self::html(status, reason(status), &content)
+    }
+
+    fn write_to(&self, mut out: impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        out.write_all(&self.body)?;
+        out.flush()
+    }
+}
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:2em auto}\
+table{border-collapse:collapse}td,th{padding:.2em .8em;text-align:left}\
+tr:nth-child(even){background:#f4f4f4}textarea{width:100%;font-family:monospace}\
+.fail{color:#b00}";
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Error",
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn content_type(name: &str) -> &'static str {
+    match name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A web page for browsing the solutions: every day with its stars and known answers, and for
+/// each day its answers and timings on the saved input, a form to run a part on any input, and
+/// the visualisations it has saved.
+pub struct Dashboard {
+    pub inputs: Inputs,
+    pub time_limit: Duration,
+    records: Mutex<HashMap<(u16, u8, u8), Saved>>,
+    /// The parts being solved right now.
+    running: Mutex<HashSet<(u16, u8, u8)>>,
+}
+
+/// A part being solved, which no other request can start solving until this is dropped.
+struct Solving<'a> {
+    running: &'a Mutex<HashSet<(u16, u8, u8)>>,
+    key: (u16, u8, u8),
+}
+
+impl Drop for Solving<'_> {
+    fn drop(&mut self) {
+        self.running.lock().unwrap().remove(&self.key);
+    }
+}
+
+/// The record of a part on its saved input, with when the input was last changed.
+type Saved = (Option<SystemTime>, Record);
+
+impl Dashboard {
+    pub fn new(inputs: Inputs) -> Self {
+        Self {
+            inputs,
+            time_limit: TIME_LIMIT,
+            records: Mutex::new(HashMap::new()),
+            running: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Where a day's visualisations go: any files in `<dir>/yearYYYY/dayDD/`.
+    pub fn visualisations_dir(&self, year: u16, day: u8) -> PathBuf {
+        self.inputs
+            .root()
+            .join(format!("year{}", year))
+            .join(format!("day{:02}", day))
+    }
+
+    /// Answers each request on its own thread until the listener fails.
+    pub fn serve(self, listener: &TcpListener) -> io::Result<()> {
+        let dashboard = Arc::new(self);
+        loop {
+            let (stream, peer) = listener.accept()?;
+            if !peer.ip().is_loopback() {
+                continue;
+            }
+            let dashboard = Arc::clone(&dashboard);
+            thread::spawn(move || {
+                if let Err(e) = dashboard.answer(stream) {
+                    eprintln!("{}: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    fn answer(self: Arc<Self>, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        let reply = match Request::read(BufReader::new(&stream)) {
+            Ok(request) => {
+                let limit = self.time_limit;
+                within(limit, move || self.handle(&request)).unwrap_or_else(|| {
+                    let message =
+                        format!("the request took longer than {}", format_duration(limit));
+                    Reply::error(503, &message)
+                })
+            }
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => Reply::error(400, &e.to_string()),
+            Err(e) => return Err(e),
+        };
+        reply.write_to(&stream)
+    }
+
+    pub fn handle(&self, request: &Request) -> Reply {
+        // a page on some other site could point its own host name at 127.0.0.1, so only
+        // requests addressed to the loopback interface itself are answered
+        let host = request.host.as_deref().unwrap_or("");
+        let name = host.rsplit_once(':').map_or(host, |(name, _)| name);
+        if !matches!(name, "127.0.0.1" | "localhost" | "[::1]") {
+            return Reply::error(403, "the dashboard only answers requests to localhost");
+        }
+
+        let segments = request
+            .path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        let day = |year: &str, day: &str| {
+            let (Ok(year), Ok(day)) = (year.parse(), day.parse()) else {
+                return Err(Reply::error(404, "no such page"));
+            };
+            registry::find(year, day).map_err(|e| Reply::error(404, &e.to_string()))
+        };
+        let result = match (request.method.as_str(), &segments[..]) {
+            ("GET", []) => Ok(self.index()),
+            ("GET", [year, d]) => day(year, d).map(|day| self.day(day, None)),
+            ("POST", [year, d]) => day(year, d).map(|day| self.run(day, &request.body)),
+            ("GET", [year, d, name]) => day(year, d).map(|day| self.visualisation(day, name)),
+            (_, [] | [_, _] | [_, _, _]) => Err(Reply::error(405, "use GET, or POST to run")),
+            _ => Err(Reply::error(404, "no such page")),
+        };
+        result.unwrap_or_else(|reply| reply)
+    }
+
+    fn history(&self) -> (Answers, History) {
+        let root = self.inputs.root();
+        let answers = Answers::load(&root.join(verify::ANSWERS_FILE)).unwrap_or_default();
+        let history = History::load(&submit::history_path(root)).unwrap_or_default();
+        (answers, history)
+    }
+
+    fn index(&self) -> Reply {
+        let (answers, history) = self.history();
+        let years = status::status(DAYS, &self.inputs, &answers, &history);
+        let mut content = String::new();
+        for YearStatus { year, days } in &years {
+            let stars = days.iter().map(|d| d.stars as u32).sum::<u32>();
+            let _ = write!(
+                content,
+                "<h2>{}</h2>\n<p>{} of {} stars</p>\n<table>\n\
+                 <tr><th>day</th><th>stars</th><th>part 1</th><th>part 2</th><th>input</th></tr>\n",
+                year,
+                stars,
+                2 * days.len()
+            );
+            for day in days.iter().filter(|d| d.solved > 0) {
+                let answer = |part| escape(answers.get(*year, day.day, part).unwrap_or(""));
+                let _ = writeln!(
+                    content,
+                    "<tr><td><a href=\"/{}/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td>\
+                     <td>{}</td></tr>",
+                    year,
+                    day.day,
+                    day.day,
+                    "⭐".repeat(day.stars as usize),
+                    answer(1),
+                    answer(2),
+                    if day.input { "saved" } else { "missing" }
+                );
+            }
+            content.push_str("</table>\n");
+        }
+        Reply::html(200, "Advent of Code", &content)
+    }
+
+    fn day(&self, day: &Day, ran: Option<(&Record, &str)>) -> Reply {
+        let (answers, _) = self.history();
+        let mut content = String::from("<p><a href=\"/\">every day</a></p>\n");
+
+        content.push_str(
+            "<h2>Saved input</h2>\n<table>\n\
+             <tr><th>part</th><th>answer</th><th>time</th><th>known answer</th></tr>\n",
+        );
+        for part in day.parts() {
+            let known = answers.get(day.year, day.day, part);
+            let Some(record) = self.saved(day, part) else {
+                let _ = writeln!(
+                    content,
+                    "<tr><td>{}</td><td>still running</td><td></td><td>{}</td></tr>",
+                    part,
+                    escape(known.unwrap_or(""))
+                );
+                continue;
+            };
+            let wrong = record.status == Status::Ok
+                && known.is_some_and(|k| Some(k) != record.answer.as_deref());
+            let _ = writeln!(
+                content,
+                "<tr><td>{}</td><td{}>{}</td><td>{}</td><td>{}</td></tr>",
+                part,
+                if wrong || record.status != Status::Ok {
+                    " class=\"fail\""
+                } else {
+                    ""
+                },
+                escape(&shown(&record)),
+                format_duration(record.duration),
+                escape(known.unwrap_or(""))
+            );
+        }
+        content.push_str("</table>\n");
+
+        let (input, chosen) = match ran {
+            Some((record, input)) => {
+                let _ = writeln!(
+                    content,
+                    "<h2>Part {} on your input</h2>\n<p>{} <small>in {}</small></p>",
+                    record.part,
+                    escape(&shown(record)),
+                    format_duration(record.duration)
+                );
+                (input, record.part)
+            }
+            None => ("", 1),
+        };
+        let options = day
+            .parts()
+            .into_iter()
+            .map(|part| {
+                let selected = if part == chosen { " selected" } else { "" };
+                format!(
+                    "<option value=\"{}\"{}>part {}</option>",
+                    part, selected, part
+                )
+            })
+            .collect::<String>();
+        let _ = write!(
+            content,
+            "<h2>Run on another input</h2>\n<form method=\"post\" action=\"/{}/{}\">\n\
+             <p><input type=\"file\" onchange=\"this.files[0].text().then(t => this.form.input.value = t)\"></p>\n\
+             <p><textarea name=\"input\" rows=\"12\">{}</textarea></p>\n\
+             <p><select name=\"part\">{}</select> <button>run</button></p>\n</form>\n",
+            day.year,
+            day.day,
+            escape(input),
+            options
+        );
+
+        let files = self.visualisation_names(day);
+        if !files.is_empty() {
+            content.push_str("<h2>Visualisations</h2>\n<ul>\n");
+            for name in files {
+                let _ = writeln!(
+                    content,
+                    "<li><a href=\"/{}/{}/{}\">{}</a></li>",
+                    day.year,
+                    day.day,
+                    escape(&name),
+                    escape(&name)
+                );
+            }
+            content.push_str("</ul>\n");
+        }
+
+        let title = format!("{} day {}", day.year, day.day);
+        Reply::html(200, &title, &content)
+    }
+
+    /// Marks `part` as being solved, unless another request is already solving it.
+    fn start(&self, day: &Day, part: u8) -> Option<Solving<'_>> {
+        let key = (day.year, day.day, part);
+        let started = self.running.lock().unwrap().insert(key);
+        started.then(|| Solving {
+            running: &self.running,
+            key,
+        })
+    }
+
+    /// The record of `part` on the day's saved input, run again only once the input changes.
+    /// None while another request is still solving it.
+    fn saved(&self, day: &Day, part: u8) -> Option<Record> {
+        let key = (day.year, day.day, part);
+        let modified = fs::metadata(self.inputs.path(day.year, day.day))
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if let Some((when, record)) = self.records.lock().unwrap().get(&key) {
+            if *when == modified {
+                return Some(record.clone());
+            }
+        }
+        let _solving = self.start(day, part)?;
+        let record = Record::run(&job(day, part), &self.inputs);
+        let mut records = self.records.lock().unwrap();
+        records.insert(key, (modified, record.clone()));
+        Some(record)
+    }
+
+    fn run(&self, day: &Day, body: &str) -> Reply {
+        let form = form_decode(body);
+        let field = |name| {
+            form.iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        let part = field("part").and_then(|p| p.parse().ok()).unwrap_or(1);
+        if !day.parts().contains(&part) {
+            return Reply::error(400, &format!("there's no solution for part {}", part));
+        }
+        // browsers send textarea line breaks as CRLF
+        let input = field("input").unwrap_or("").replace("\r\n", "\n");
+        let Some(solving) = self.start(day, part) else {
+            let message = format!(
+                "{} day {} part {} is still running from an earlier request",
+                day.year, day.day, part
+            );
+            return Reply::error(503, &message);
+        };
+        let record = Record::solve(&job(day, part), &input);
+        drop(solving);
+        self.day(day, Some((&record, &input)))
+    }
+
+    fn visualisation_names(&self, day: &Day) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.visualisations_dir(day.year, day.day)) else {
+            return Vec::new();
+        };
+        let mut names = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    fn visualisation(&self, day: &Day, name: &str) -> Reply {
+        // only names that are listed are served, so nothing outside the directory can be
+        if !self.visualisation_names(day).iter().any(|n| n == name) {
+            return Reply::error(
+                404,
+                &format!("{} day {} has no {}", day.year, day.day, name),
+            );
+        }
+        match fs::read(self.visualisations_dir(day.year, day.day).join(name)) {
+            Ok(body) => Reply {
+                status: 200,
+                content_type: content_type(name),
+                body,
+            },
+            Err(e) => Reply::error(404, &e.to_string()),
+        }
+    }
+}
+
+/// Runs `f` on a thread of its own, giving up on it after `limit`.
+fn within<T: Send + 'static>(limit: Duration, f: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(f()));
+    receiver.recv_timeout(limit).ok()
+}
+
+fn job(day: &Day, part: u8) -> Job {
+    Job {
+        year: day.year,
+        day: day.day,
+        part,
+        solve: day.part(part).unwrap(),
+    }
+}
+
+fn shown(record: &Record) -> String {
+    match &record.answer {
+        Some(answer) => answer.clone(),
+        None => format!(
+            "{}: {}",
+            record.status.name(),
+            record.error.as_deref().unwrap_or("")
+        ),
+    }
+}
+
+/// Serves the dashboard on `port` until the process is stopped.
+pub fn run(port: u16, inputs: Inputs) -> Result<(), CommandError> {
+    let listener = bind(port)?;
+    println!("serving on http://{}/", listener.local_addr()?);
+    Dashboard::new(inputs).serve(&listener)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    fn dashboard(test: &str) -> (Dashboard, Scratch) {
+        let dir = Scratch::new(&format!("serve-{}", test));
+        fs::create_dir_all(dir.join("year2022").join("day01")).unwrap();
+        fs::write(dir.join("year2022").join("day01.txt"), "1\n2\n\n4\n").unwrap();
+        fs::write(
+            dir.join("year2022").join("day01").join("elves.svg"),
+            "<svg/>",
+        )
+        .unwrap();
+        fs::write(dir.join(verify::ANSWERS_FILE), "2022 1 1 4\n2022 1 2 7\n").unwrap();
+        (Dashboard::new(Inputs::new(&*dir)), dir)
+    }
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_owned(),
+            path: path.to_owned(),
+            host: Some("127.0.0.1:8025".to_owned()),
+            body: body.to_owned(),
+        }
+    }
+
+    fn page(reply: &Reply) -> &str {
+        std::str::from_utf8(&reply.body).unwrap()
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw =
+            "POST /2022/1?x=1 HTTP/1.1\r\nHost: localhost:8025\r\nContent-Length: 6\r\n\r\npart=2";
+        let request = Request::read(raw.as_bytes()).unwrap();
+        assert_eq!(
+            request,
+            Request {
+                method: "POST".to_owned(),
+                path: "/2022/1".to_owned(),
+                host: Some("localhost:8025".to_owned()),
+                body: "part=2".to_owned(),
+            }
+        );
+        let huge = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY + 1
+        );
+        assert!(Request::read(huge.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_pages() {
+        let (dashboard, _dir) = dashboard("pages");
+
+        let index = dashboard.handle(&request("GET", "/", ""));
+        assert_eq!(index.status, 200);
+        assert!(page(&index).contains(
+            "<a href=\"/2022/1\">1</a></td><td>⭐⭐</td><td>4</td><td>7</td><td>saved</td>"
+        ));
+        assert!(page(&index).contains("<td>missing</td>"));
+
+        let day = dashboard.handle(&request("GET", "/2022/1", ""));
+        assert!(page(&day).contains("<h1>2022 day 1</h1>"));
+        assert!(page(&day).contains("<tr><td>2</td><td>7</td>"));
+        assert!(page(&day).contains("<a href=\"/2022/1/elves.svg\">elves.svg</a>"));
+
+        let ran = dashboard.handle(&request("POST", "/2022/1", "input=5%0D%0A%0D%0A6&part=2"));
+        assert!(page(&ran).contains("<h2>Part 2 on your input</h2>\n<p>11 <small>"));
+        assert!(page(&ran).contains("<option value=\"2\" selected>"));
+        let invalid = dashboard.handle(&request("POST", "/2022/1", "input=x&part=1"));
+        assert!(page(&invalid).contains(
+            "<p>invalid_input: line 1, column 1: expected a number, found &quot;x&quot; <small>"
+        ));
+    }
+
+    #[test]
+    fn test_saved_records() {
+        let (dashboard, dir) = dashboard("saved-records");
+        dashboard.handle(&request("GET", "/2022/1", ""));
+        assert_eq!(dashboard.records.lock().unwrap().len(), 2);
+
+        // a cached record is shown as long as the input hasn't changed
+        let key = (2022, 1, 2);
+        dashboard
+            .records
+            .lock()
+            .unwrap()
+            .get_mut(&key)
+            .unwrap()
+            .1
+            .answer = Some("8".to_owned());
+        let day = dashboard.handle(&request("GET", "/2022/1", ""));
+        assert!(page(&day).contains("<tr><td>2</td><td class=\"fail\">8</td>"));
+
+        fs::remove_file(dir.join("year2022").join("day01.txt")).unwrap();
+        let day = dashboard.handle(&request("GET", "/2022/1", ""));
+        assert!(page(&day).contains("<tr><td>2</td><td class=\"fail\">no_input: "));
+    }
+
+    #[test]
+    fn test_one_solve_per_part() {
+        let (dashboard, _dir) = dashboard("one-solve");
+        let day = registry::find(2022, 1).unwrap();
+        let solving = dashboard.start(day, 2).unwrap();
+        assert!(dashboard.start(day, 2).is_none());
+
+        let refused = dashboard.handle(&request("POST", "/2022/1", "input=5&part=2"));
+        assert_eq!(refused.status, 503);
+        assert!(page(&refused).contains("2022 day 1 part 2 is still running"));
+        let page_while_running = dashboard.handle(&request("GET", "/2022/1", ""));
+        assert!(page(&page_while_running).contains("<tr><td>2</td><td>still running</td>"));
+        assert!(page(&page_while_running).contains("<tr><td>1</td><td>4</td>"));
+
+        drop(solving);
+        let ran = dashboard.handle(&request("POST", "/2022/1", "input=5&part=2"));
+        assert_eq!(ran.status, 200);
+        assert!(dashboard.running.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_within() {
+        assert_eq!(within(Duration::from_secs(10), || 1), Some(1));
+        let slow = || thread::sleep(Duration::from_secs(1));
+        assert_eq!(within(Duration::from_millis(10), slow), None);
+    }
+
+    #[test]
+    fn test_visualisations() {
+        let (dashboard, _dir) = dashboard("visualisations");
+        let svg = dashboard.handle(&request("GET", "/2022/1/elves.svg", ""));
+        assert_eq!((svg.status, svg.content_type), (200, "image/svg+xml"));
+        assert_eq!(svg.body, b"<svg/>");
+        let outside = dashboard.handle(&request("GET", "/2022/1/..%2Fday01.txt", ""));
+        assert_eq!(outside.status, 404);
+    }
+
+    #[test]
+    fn test_refusals() {
+        let (dashboard, _dir) = dashboard("refusals");
+        let status = |method, path| dashboard.handle(&request(method, path, "")).status;
+        assert_eq!(status("GET", "/2020/1"), 404);
+        assert_eq!(status("GET", "/2022/x"), 404);
+        assert_eq!(status("GET", "/a/b/c/d"), 404);
+        assert_eq!(status("DELETE", "/2022/1"), 405);
+        let mut rebound = request("GET", "/", "");
+        rebound.host = Some("evil.example:8025".to_owned());
+        assert_eq!(dashboard.handle(&rebound).status, 403);
+    }
+
+    #[test]
+    fn test_serve() {
+        let (dashboard, _dir) = dashboard("serve");
+        let listener = bind(0).unwrap();
+        let address = listener.local_addr().unwrap();
+        assert!(address.ip().is_loopback());
+        std::thread::spawn(move || dashboard.serve(&listener));
+
+        let response = crate::http::get(&format!("http://{}/2022/1", address), &[]).unwrap();
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("2022 day 1"));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::command::{usage, CommandError};
+use crate::input::Inputs;
+use crate::year2019::replay::{record_to, replay_file};
+use crate::year2019::vm::{parse_program, Console, Intcode};
+use std::path::Path;
+
+/// The days whose input is an Intcode program, the only ones with sessions to record.
+const INTCODE_DAYS: [(u16, u8); 3] = [(2019, 2), (2019, 5), (2019, 25)];
+
+/// The Intcode program a day reads as its input.
+fn program(year: u16, day: u8, inputs: &Inputs) -> Result<Vec<i64>, CommandError> {
+    if !INTCODE_DAYS.contains(&(year, day)) {
+        return Err(usage(format!(
+            "{} day {} doesn't take an Intcode program",
+            year, day
+        )));
+    }
+    Ok(parse_program(&inputs.read(year, day)?)?)
+}
+
+/// Runs a day's program interactively on the console, saving the session to `path`.
+pub fn run_record(year: u16, day: u8, path: &Path, inputs: &Inputs) -> Result<(), CommandError> {
+    let mut memory = program(year, day, inputs)?;
+    let status = record_to(&mut Intcode::new(&mut memory), &mut Console, path)?;
+    eprintln!("{}; saved the session to {}", status, path.display());
+    Ok(())
+}
+
+/// Replays the session saved in `path` against a day's program, failing where it diverges.
+pub fn run_replay(year: u16, day: u8, path: &Path, inputs: &Inputs) -> Result<(), CommandError> {
+    match replay_file(&program(year, day, inputs)?, path)? {
+        Ok(status) => println!("replayed {}: {}", path.display(), status),
+        Err(divergence) => {
+            println!("{}", divergence);
+            return Err(CommandError::Failed);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::DEFAULT_DATA_DIR;
+
+    #[test]
+    fn test_only_intcode_days() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        let error = run_replay(2022, 1, Path::new("session.txt"), &inputs).unwrap_err();
+        assert!(matches!(error, CommandError::Usage(_)));
+        assert_eq!(
+            error.to_string(),
+            "2022 day 1 doesn't take an Intcode program"
+        );
+        assert!(program(2019, 5, &inputs).is_ok());
+    }
+}
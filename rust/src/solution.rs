@@ -0,0 +1,53 @@
+use crate::anonymise::{self, Rng};
+use crate::error::ParseError;
+
+/// A day's puzzle: how to read the input, and how to answer each part from it.
+pub trait Solution {
+    type Input<'a>;
+    type Answer1: Answer;
+    type Answer2: Answer;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError>;
+    fn part1(input: &Self::Input<'_>) -> Self::Answer1;
+    fn part2(input: &Self::Input<'_>) -> Self::Answer2;
+
+    /// Makes up an input shaped like `data` that can be shared in place of it. Days whose
+    /// inputs can't be made up return why.
+    fn synthesise(data: &str, rng: &mut Rng) -> Result<String, &'static str> {
+        Ok(anonymise::synthesise(data, rng))
+    }
+}
+
+/// What a part gives: an answer, or for a part that can find an input it parsed has no answer,
+/// a `Result` saying why.
+pub trait Answer {
+    fn into_answer(self) -> Result<String, ParseError>;
+}
+
+macro_rules! answer {
+    ($($t:ty),*) => {$(
+        impl Answer for $t {
+            fn into_answer(self) -> Result<String, ParseError> {
+                Ok(self.to_string())
+            }
+        }
+    )*};
+}
+
+answer!(i32, i64, u32, u64, usize, String);
+
+impl<T: Answer> Answer for Result<T, ParseError> {
+    fn into_answer(self) -> Result<String, ParseError> {
+        self?.into_answer()
+    }
+}
+
+/// Parses `data` and answers part 1, for callers that don't know the day's types.
+pub fn solve_part1<S: Solution>(data: &str) -> Result<String, ParseError> {
+    S::part1(&S::parse(data)?).into_answer()
+}
+
+/// Parses `data` and answers part 2, for callers that don't know the day's types.
+pub fn solve_part2<S: Solution>(data: &str) -> Result<String, ParseError> {
+    S::part2(&S::parse(data)?).into_answer()
+}
@@ -0,0 +1,259 @@
+use crate::command::CommandError;
+use crate::download::days_in;
+use crate::input::Inputs;
+use crate::registry::{self, Day};
+use crate::submit::{self, History, Verdict};
+use crate::verify::{self, Answers};
+use std::fmt::{Display, Formatter};
+
+/// How far along one day is.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct DayStatus {
+    pub day: u8,
+    /// Parts with a solution in the crate.
+    pub solved: u8,
+    /// Parts whose answer is known to be right, from the known answers or an accepted submission.
+    pub stars: u8,
+    pub input: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct YearStatus {
+    pub year: u16,
+    /// Every day of the event, whether or not there's anything for it yet.
+    pub days: Vec<DayStatus>,
+}
+
+impl YearStatus {
+    pub fn stars(&self) -> u32 {
+        self.days.iter().map(|d| d.stars as u32).sum()
+    }
+}
+
+/// The status of every year with a solution.
+pub fn status(
+    days: &[Day],
+    inputs: &Inputs,
+    answers: &Answers,
+    history: &History,
+) -> Vec<YearStatus> {
+    let mut years = days.iter().map(|d| d.year).collect::<Vec<_>>();
+    years.dedup();
+    years
+        .into_iter()
+        .map(|year| YearStatus {
+            year,
+            days: (1..=days_in(year))
+                .map(|day| {
+                    let registered = days.iter().find(|d| (d.year, d.day) == (year, day));
+                    let starred = |part| {
+                        answers.get(year, day, part).is_some()
+                            || history
+                                .attempts(year, day, part)
+                                .any(|a| a.verdict == Verdict::Correct)
+                    };
+                    DayStatus {
+                        day,
+                        solved: registered.map_or(0, |d| d.parts().len() as u8),
+                        stars: (1..=2).filter(|&part| starred(part)).count() as u8,
+                        input: inputs.exists(year, day),
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn days_without_input(year: &YearStatus) -> Vec<String> {
+    year.days
+        .iter()
+        .filter(|d| d.solved > 0 && !d.input)
+        .map(|d| d.day.to_string())
+        .collect()
+}
+
+/// Lays the years out as a calendar for the terminal: `**` for two stars, `*` for one, and `.`
+/// for a day that's solved without a known answer.
+pub struct Grid<'a>(pub &'a [YearStatus]);
+
+impl Display for Grid<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self.0.iter().map(|y| y.days.len()).max().unwrap_or(0);
+        write!(f, "    ")?;
+        for day in 1..=width {
+            write!(f, " {:>2}", day)?;
+        }
+        writeln!(f, "  stars")?;
+
+        for year in self.0 {
+            write!(f, "{}", year.year)?;
+            for day in &year.days {
+                let cell = match (day.stars, day.solved) {
+                    (2, _) => "**",
+                    (1, _) => "* ",
+                    (0, 0) => "  ",
+                    _ => ". ",
+                };
+                write!(f, " {}", cell)?;
+            }
+            let padding = 3 * (width - year.days.len());
+            writeln!(
+                f,
+                "{:padding$}  {:>2}/{}",
+                "",
+                year.stars(),
+                2 * year.days.len()
+            )?;
+        }
+
+        let missing = self
+            .0
+            .iter()
+            .filter_map(|year| {
+                let days = days_without_input(year);
+                (!days.is_empty()).then(|| format!("{} day {}", year.year, days.join(", ")))
+            })
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            write!(f, "every solved day has an input")
+        } else {
+            write!(f, "no input for {}", missing.join("; "))
+        }
+    }
+}
+
+/// Lays the years out as a Markdown table, for the README.
+pub struct Markdown<'a>(pub &'a [YearStatus]);
+
+impl Display for Markdown<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self.0.iter().map(|y| y.days.len()).max().unwrap_or(0);
+        write!(f, "| Year |")?;
+        for day in 1..=width {
+            write!(f, " {} |", day)?;
+        }
+        writeln!(f, " Stars |")?;
+        writeln!(f, "|---|{}---|", "---|".repeat(width))?;
+
+        for year in self.0 {
+            write!(f, "| {} |", year.year)?;
+            for day in 1..=width {
+                let cell = match year.days.get(day - 1) {
+                    Some(day) => match (day.stars, day.solved) {
+                        (0, 0) => "",
+                        (0, _) => "·",
+                        (1, _) => "⭐",
+                        _ => "⭐⭐",
+                    },
+                    None => "",
+                };
+                write!(f, " {} |", cell)?;
+            }
+            writeln!(f, " {} |", year.stars())?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the stars of every year as a calendar, or as a Markdown table.
+pub fn run(inputs: &Inputs, markdown: bool) -> Result<(), CommandError> {
+    let answers = Answers::load(&inputs.root().join(verify::ANSWERS_FILE))?;
+    let history = History::load(&submit::history_path(inputs.root()))?;
+    let years = status(registry::DAYS, inputs, &answers, &history);
+    if markdown {
+        print!("{}", Markdown(&years));
+    } else {
+        println!("{}", Grid(&years));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DAYS;
+
+    fn year(year: u16, days: &[(u8, u8, u8, bool)]) -> YearStatus {
+        YearStatus {
+            year,
+            days: (1..=days_in(year))
+                .map(|day| {
+                    let status = days.iter().find(|d| d.0 == day);
+                    status.map_or(
+                        DayStatus {
+                            day,
+                            ..DayStatus::default()
+                        },
+                        |&(day, solved, stars, input)| DayStatus {
+                            day,
+                            solved,
+                            stars,
+                            input,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let inputs = Inputs::new(crate::input::DEFAULT_DATA_DIR);
+        let answers =
+            Answers::parse("2019 1 1 3330521\n2019 1 2 4992931\n2019 2 1 2894520").unwrap();
+        let history =
+            History::parse("1\t2019\t3\t2\tcorrect\t66076\n1\t2019\t4\t1\ttoo-low\t1").unwrap();
+        let years = status(DAYS, &inputs, &answers, &history);
+        assert_eq!(
+            years.iter().map(|y| y.year).collect::<Vec<_>>(),
+            [2019, 2021, 2022, 2024]
+        );
+
+        let days = &years[0].days;
+        assert_eq!(days.len(), 25);
+        let stars = days.iter().map(|d| d.stars).take(5).collect::<Vec<_>>();
+        assert_eq!(stars, [2, 1, 1, 0, 0]);
+        assert_eq!(days[24].solved, 1);
+        assert!(!days[24].input);
+        assert_eq!(
+            days[6],
+            DayStatus {
+                day: 7,
+                ..DayStatus::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let years = [
+            year(2019, &[(1, 2, 2, true), (2, 2, 1, true), (25, 1, 0, false)]),
+            year(2025, &[(1, 2, 0, true)]),
+        ];
+        let grid = Grid(&years).to_string();
+        let lines = grid.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("      1  2  3"));
+        assert!(lines[0].ends_with(" 24 25  stars"));
+        assert!(lines[1].starts_with("2019 ** *    "));
+        assert!(lines[1].ends_with(" .    3/50"));
+        assert!(lines[2].starts_with("2025 . "));
+        assert!(lines[2].ends_with("   0/24"));
+        assert_eq!(lines[1].len(), lines[2].len());
+        assert_eq!(lines[3], "no input for 2019 day 25");
+    }
+
+    #[test]
+    fn test_markdown() {
+        let years = [year(
+            2024,
+            &[(1, 2, 2, true), (2, 2, 1, true), (3, 2, 0, true)],
+        )];
+        let markdown = Markdown(&years).to_string();
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("| Year | 1 | 2 |"));
+        assert!(lines[0].ends_with("| 25 | Stars |"));
+        assert_eq!(lines[1], format!("|---|{}---|", "---|".repeat(25)));
+        assert!(lines[2].starts_with("| 2024 | ⭐⭐ | ⭐ | · |  |"));
+        assert!(lines[2].ends_with("|  | 3 |"));
+    }
+}
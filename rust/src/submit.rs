@@ -0,0 +1,520 @@
+use crate::command::CommandError;
+use crate::download::{self, DownloadError, Downloader};
+use crate::http;
+use crate::input::Inputs;
+use crate::runner::{resolve, Selection};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Where attempts are kept, inside the data directory.
+pub const HISTORY_FILE: &str = "submissions.txt";
+
+/// What the site said about an answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Answered too recently; the site gives how long is left, in seconds.
+    Wait(Option<u64>),
+    /// The part was already solved, or part 1 hasn't been yet.
+    WrongLevel,
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page the site sends back.
+    pub fn parse(page: &str) -> Self {
+        let text = article(page);
+        if text.contains("That's the right answer") {
+            Verdict::Correct
+        } else if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else if text.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if text.contains("You gave an answer too recently") {
+            Verdict::Wait(wait_seconds(&text))
+        } else if text.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown(text)
+        }
+    }
+
+    /// Whether the answer is definitely not the solution.
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+
+    fn key(&self) -> String {
+        match self {
+            Verdict::Correct => "correct".to_owned(),
+            Verdict::TooHigh => "too-high".to_owned(),
+            Verdict::TooLow => "too-low".to_owned(),
+            Verdict::Wrong => "wrong".to_owned(),
+            Verdict::Wait(Some(seconds)) => format!("wait-{}", seconds),
+            Verdict::Wait(None) => "wait".to_owned(),
+            Verdict::WrongLevel => "wrong-level".to_owned(),
+            Verdict::Unknown(_) => "unknown".to_owned(),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "correct" => Verdict::Correct,
+            "too-high" => Verdict::TooHigh,
+            "too-low" => Verdict::TooLow,
+            "wrong" => Verdict::Wrong,
+            "wait" => Verdict::Wait(None),
+            "wrong-level" => Verdict::WrongLevel,
+            "unknown" => Verdict::Unknown(String::new()),
+            key => Verdict::Wait(Some(key.strip_prefix("wait-")?.parse().ok()?)),
+        })
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "that's the right answer"),
+            Verdict::TooHigh => write!(f, "that's too high"),
+            Verdict::TooLow => write!(f, "that's too low"),
+            Verdict::Wrong => write!(f, "that's not the right answer"),
+            Verdict::Wait(Some(seconds)) => write!(
+                f,
+                "answered too recently; wait {}m {}s",
+                seconds / 60,
+                seconds % 60
+            ),
+            Verdict::Wait(None) => write!(f, "answered too recently; wait a bit"),
+            Verdict::WrongLevel => {
+                write!(f, "that part is already solved, or the one before it isn't")
+            }
+            Verdict::Unknown(text) => write!(f, "unrecognised response: {}", text),
+        }
+    }
+}
+
+/// The text of the `<article>` in a page, with the tags stripped.
+fn article(page: &str) -> String {
+    let start = page.find("<article").unwrap_or(0);
+    let end = page[start..]
+        .find("</article>")
+        .map_or(page.len(), |end| start + end);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in page[start..end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Reads "You have 1m 30s left to wait" as seconds.
+fn wait_seconds(text: &str) -> Option<u64> {
+    let rest = &text[text.find("You have ")? + "You have ".len()..];
+    let rest = &rest[..rest.find(" left")?];
+    rest.split_whitespace()
+        .map(|part| {
+            let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let number = number.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(number * 3600),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+/// One answer sent to the site.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Attempt {
+    pub time: u64,
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+/// Every answer sent so far. Saved as tab separated lines of time, year, day, part, verdict
+/// and answer.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct History {
+    pub attempts: Vec<Attempt>,
+}
+
+/// Whether an answer is worth sending.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Check {
+    Unknown,
+    AlreadyCorrect(String),
+    KnownWrong(Verdict),
+    /// Lies outside what earlier too high and too low answers allow.
+    OutOfBounds {
+        low: Option<i128>,
+        high: Option<i128>,
+    },
+}
+
+impl History {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut attempts = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = line.splitn(6, '\t').collect::<Vec<_>>();
+            let attempt = match fields[..] {
+                [time, year, day, part, verdict, answer] => (|| {
+                    Some(Attempt {
+                        time: time.parse().ok()?,
+                        year: year.parse().ok()?,
+                        day: day.parse().ok()?,
+                        part: part.parse().ok()?,
+                        verdict: Verdict::from_key(verdict)?,
+                        answer: answer.to_owned(),
+                    })
+                })(),
+                _ => None,
+            };
+            match attempt {
+                Some(attempt) => attempts.push(attempt),
+                None => return Err(format!("line {}: invalid attempt {:?}", number + 1, line)),
+            }
+        }
+        Ok(Self { attempts })
+    }
+
+    /// Loads the history, or an empty one if there isn't a file yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Appends `attempt` to the file at `path` as well as to the history.
+    pub fn record(&mut self, path: &Path, attempt: Attempt) -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            attempt.time,
+            attempt.year,
+            attempt.day,
+            attempt.part,
+            attempt.verdict.key(),
+            attempt.answer
+        )?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    pub fn attempts(&self, year: u16, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.year == year && a.day == day && a.part == part)
+    }
+
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Check {
+        let mut low = None;
+        let mut high = None;
+        for attempt in self.attempts(year, day, part) {
+            if attempt.verdict == Verdict::Correct {
+                return Check::AlreadyCorrect(attempt.answer.clone());
+            }
+            if attempt.answer == answer && attempt.verdict.is_wrong() {
+                return Check::KnownWrong(attempt.verdict.clone());
+            }
+            if let Ok(value) = attempt.answer.parse::<i128>() {
+                match attempt.verdict {
+                    Verdict::TooLow => low = low.max(Some(value)),
+                    Verdict::TooHigh => high = Some(high.map_or(value, |h: i128| h.min(value))),
+                    _ => {}
+                }
+            }
+        }
+
+        match answer.parse::<i128>() {
+            Ok(value)
+                if low.is_some_and(|low| value <= low)
+                    || high.is_some_and(|high| value >= high) =>
+            {
+                Check::OutOfBounds { low, high }
+            }
+            _ => Check::Unknown,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The history already says this answer is correct or wrong, or that it can't be right.
+    Refused(Check),
+    Download(DownloadError),
+    Io(io::Error),
+}
+
+impl Display for SubmitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::Refused(Check::AlreadyCorrect(answer)) => {
+                write!(f, "already solved with {}", answer)
+            }
+            SubmitError::Refused(Check::KnownWrong(verdict)) => {
+                write!(f, "already sent that answer and {}", verdict)
+            }
+            SubmitError::Refused(Check::OutOfBounds { low, high }) => {
+                let bound = |b: &Option<i128>| b.map_or("?".to_owned(), |b| b.to_string());
+                write!(
+                    f,
+                    "the answer must be between {} and {} going by earlier attempts; pass --force to send it anyway",
+                    bound(low),
+                    bound(high)
+                )
+            }
+            SubmitError::Refused(Check::Unknown) => write!(f, "refused"),
+            SubmitError::Download(e) => write!(f, "{}", e),
+            SubmitError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<DownloadError> for SubmitError {
+    fn from(e: DownloadError) -> Self {
+        SubmitError::Download(e)
+    }
+}
+
+impl From<io::Error> for SubmitError {
+    fn from(e: io::Error) -> Self {
+        SubmitError::Io(e)
+    }
+}
+
+/// Sends `answer` for a part, after checking it against the history kept at `path`, and records
+/// what the site says. With `force`, only answers the site has already judged are held back.
+pub fn submit(
+    downloader: &mut Downloader,
+    path: &Path,
+    (year, day, part): (u16, u8, u8),
+    answer: &str,
+    force: bool,
+) -> Result<Verdict, SubmitError> {
+    let mut history = History::load(path)?;
+    match history.check(year, day, part, answer) {
+        Check::Unknown => {}
+        Check::OutOfBounds { .. } if force => {}
+        check => return Err(SubmitError::Refused(check)),
+    }
+
+    let form = http::form_encode(&[("level", &part.to_string()), ("answer", answer)]);
+    let response = downloader.request(&format!("/{}/day/{}/answer", year, day), Some(&form))?;
+    let verdict = Verdict::parse(&response.body);
+
+    history.record(
+        path,
+        Attempt {
+            time: crate::download::now(),
+            year,
+            day,
+            part,
+            verdict: verdict.clone(),
+            answer: answer.to_owned(),
+        },
+    )?;
+    Ok(verdict)
+}
+
+/// Where the history lives for a data directory.
+pub fn history_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(HISTORY_FILE)
+}
+
+/// Answers a part and sends the answer, unless it's been ruled out.
+pub fn run(
+    (year, day, part): (u16, u8, u8),
+    inputs: &Inputs,
+    force: bool,
+) -> Result<(), CommandError> {
+    let jobs = resolve(Selection::Part(year, day, part))?;
+    download::fetch_missing(inputs, &jobs);
+    let answer = (jobs[0].solve)(&inputs.read(year, day)?)?;
+    println!("{} day {} part {}: {}", year, day, part, answer);
+
+    let mut downloader = Downloader::configure()?;
+    let history = history_path(inputs.root());
+    let verdict = submit(&mut downloader, &history, (year, day, part), &answer, force)?;
+    println!("{}", verdict);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::{respond, serve};
+    use crate::scratch::Scratch;
+    use std::time::Duration;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    fn attempt(part: u8, verdict: Verdict, answer: &str) -> Attempt {
+        Attempt {
+            time: 1,
+            year: 2019,
+            day: 3,
+            part,
+            verdict,
+            answer: answer.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        let verdict = |message| Verdict::parse(&page(message));
+        assert_eq!(
+            verdict("That's the right answer!  You are <em>one gold star</em> closer."),
+            Verdict::Correct
+        );
+        assert_eq!(
+            verdict("That's not the right answer; your answer is too high.  If you're stuck..."),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            verdict("That's not the right answer; your answer is too low."),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            verdict("That's not the right answer.  If you're stuck, make sure..."),
+            Verdict::Wrong
+        );
+        assert_eq!(
+            verdict("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 30s left to wait."),
+            Verdict::Wait(Some(90))
+        );
+        assert_eq!(
+            verdict("You don't seem to be solving the right level.  Did you already complete it?"),
+            Verdict::WrongLevel
+        );
+        assert_eq!(
+            verdict("Something <b>new</b>"),
+            Verdict::Unknown("Something new".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let attempts = vec![
+            attempt(1, Verdict::TooHigh, "500"),
+            attempt(1, Verdict::Wait(Some(42)), "400"),
+            attempt(2, Verdict::Correct, "a b c"),
+        ];
+        let text = "1\t2019\t3\t1\ttoo-high\t500\n1\t2019\t3\t1\twait-42\t400\n1\t2019\t3\t2\tcorrect\ta b c\n";
+        assert_eq!(History::parse(text), Ok(History { attempts }));
+        assert_eq!(
+            History::parse("1\t2019\t3\t1\tmaybe\t500"),
+            Err("line 1: invalid attempt \"1\\t2019\\t3\\t1\\tmaybe\\t500\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let history = History {
+            attempts: vec![
+                attempt(1, Verdict::TooHigh, "500"),
+                attempt(1, Verdict::TooLow, "100"),
+                attempt(1, Verdict::TooHigh, "400"),
+                attempt(1, Verdict::Wrong, "250"),
+                attempt(2, Verdict::Correct, "7"),
+            ],
+        };
+        assert_eq!(history.check(2019, 3, 1, "300"), Check::Unknown);
+        assert_eq!(
+            history.check(2019, 3, 1, "250"),
+            Check::KnownWrong(Verdict::Wrong)
+        );
+        assert_eq!(
+            history.check(2019, 3, 1, "450"),
+            Check::OutOfBounds {
+                low: Some(100),
+                high: Some(400)
+            }
+        );
+        assert_eq!(
+            history.check(2019, 3, 1, "100"),
+            Check::KnownWrong(Verdict::TooLow)
+        );
+        assert_eq!(
+            history.check(2019, 3, 2, "8"),
+            Check::AlreadyCorrect("7".to_owned())
+        );
+        assert_eq!(history.check(2019, 4, 1, "1"), Check::Unknown);
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = serve(vec![
+            respond(
+                200,
+                &page("That's not the right answer; your answer is too low."),
+            ),
+            respond(200, &page("That's the right answer!")),
+        ]);
+        let dir = Scratch::new("submit");
+        let path = dir.join("submissions.txt");
+        let mut downloader = Downloader::new(url, "abc123").with_min_interval(Duration::ZERO);
+
+        let verdict = submit(&mut downloader, &path, (2019, 3, 1), "150", false).unwrap();
+        assert_eq!(verdict, Verdict::TooLow);
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2019/day/3/answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("level=1&answer=150"));
+
+        // never sent: the site already said so, or it can't be right
+        assert!(matches!(
+            submit(&mut downloader, &path, (2019, 3, 1), "150", false),
+            Err(SubmitError::Refused(Check::KnownWrong(Verdict::TooLow)))
+        ));
+        assert!(matches!(
+            submit(&mut downloader, &path, (2019, 3, 1), "120", false),
+            Err(SubmitError::Refused(Check::OutOfBounds { .. }))
+        ));
+
+        let verdict = submit(&mut downloader, &path, (2019, 3, 1), "806", false).unwrap();
+        assert_eq!(verdict, Verdict::Correct);
+        let history = History::load(&path).unwrap();
+        let answers = history
+            .attempts
+            .iter()
+            .map(|a| (a.answer.as_str(), a.verdict.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            [("150", Verdict::TooLow), ("806", Verdict::Correct)]
+        );
+    }
+}
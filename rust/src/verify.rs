@@ -0,0 +1,245 @@
+use crate::command::CommandError;
+use crate::input::Inputs;
+use crate::registry::Day;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Where verified answers are kept, inside the data directory.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Answers known to be right for the inputs in a data directory, by year, day and part.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    pub answers: BTreeMap<(u16, u8, u8), String>,
+}
+
+impl Answers {
+    /// Parses `year day part answer` lines, ignoring blank lines and `#` comments.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut answers = BTreeMap::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.splitn(4, ' ').collect::<Vec<_>>();
+            let entry = match fields[..] {
+                [year, day, part, answer] => match (year.parse(), day.parse(), part.parse()) {
+                    (Ok(year), Ok(day), Ok(part)) => Some(((year, day, part), answer.to_owned())),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match entry {
+                Some((key, answer)) => {
+                    answers.insert(key, answer);
+                }
+                None => return Err(format!("line {}: invalid answer {:?}", number + 1, line)),
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    /// Loads the answers, or none if there isn't a file yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(|a| a.as_str())
+    }
+}
+
+/// How one part did against its known answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// There's no known answer to compare with.
+    Missing,
+    NoInput(String),
+    /// The day couldn't parse its input.
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Report {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub outcome: Outcome,
+}
+
+/// Runs both parts of every day in `days` and compares them with `answers`.
+pub fn verify(days: &[&Day], inputs: &Inputs, answers: &Answers) -> Vec<Report> {
+    let mut reports = Vec::new();
+    for day in days {
+        let data = inputs.read(day.year, day.day);
+        for part in 1..=2 {
+            let Some(solve) = day.part(part) else {
+                continue;
+            };
+            let expected = answers.get(day.year, day.day, part);
+            let (answer, outcome) = match &data {
+                Err(e) => (None, Outcome::NoInput(e.to_string())),
+                Ok(data) => {
+                    let answer = match solve(data) {
+                        Ok(answer) => answer,
+                        Err(e) => {
+                            reports.push(Report {
+                                year: day.year,
+                                day: day.day,
+                                part,
+                                answer: None,
+                                outcome: Outcome::Invalid(e.to_string()),
+                            });
+                            continue;
+                        }
+                    };
+                    let outcome = match expected {
+                        Some(expected) if expected == answer => Outcome::Pass,
+                        Some(expected) => Outcome::Fail {
+                            expected: expected.to_owned(),
+                        },
+                        None => Outcome::Missing,
+                    };
+                    (Some(answer), outcome)
+                }
+            };
+            reports.push(Report {
+                year: day.year,
+                day: day.day,
+                part,
+                answer,
+                outcome,
+            });
+        }
+    }
+    reports
+}
+
+/// Lays reports out as a table with a summary line.
+pub struct Table<'a>(pub &'a [Report]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "year  day  part  status    answer")?;
+        for report in self.0 {
+            let (status, detail) = match &report.outcome {
+                Outcome::Pass => ("pass", String::new()),
+                Outcome::Fail { expected } => ("FAIL", format!(" (expected {})", expected)),
+                Outcome::Missing => ("missing", String::new()),
+                Outcome::NoInput(e) => ("no input", e.clone()),
+                Outcome::Invalid(e) => ("invalid", e.clone()),
+            };
+            writeln!(
+                f,
+                "{:<4}  {:>3}  {:>4}  {:<8}  {}{}",
+                report.year,
+                report.day,
+                report.part,
+                status,
+                report.answer.as_deref().unwrap_or(""),
+                detail
+            )?;
+        }
+
+        let count = |f: fn(&Outcome) -> bool| self.0.iter().filter(|r| f(&r.outcome)).count();
+        write!(
+            f,
+            "{} passed, {} failed, {} missing, {} without input",
+            count(|o| *o == Outcome::Pass),
+            count(|o| matches!(o, Outcome::Fail { .. } | Outcome::Invalid(_))),
+            count(|o| *o == Outcome::Missing),
+            count(|o| matches!(o, Outcome::NoInput(_)))
+        )
+    }
+}
+
+/// Checks `days` against the known answers, failing if any answer is wrong.
+pub fn run(days: &[&Day], inputs: &Inputs) -> Result<(), CommandError> {
+    let answers = Answers::load(&inputs.root().join(ANSWERS_FILE))?;
+    let reports = verify(days, inputs, &answers);
+    println!("{}", Table(&reports));
+    if reports
+        .iter()
+        .any(|r| matches!(r.outcome, Outcome::Fail { .. } | Outcome::Invalid(_)))
+    {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::DEFAULT_DATA_DIR;
+    use crate::registry::DAYS;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("# known\n2019 3 1 806\n\n2022 2 2 a b\n").unwrap();
+        assert_eq!(answers.get(2019, 3, 1), Some("806"));
+        assert_eq!(answers.get(2022, 2, 2), Some("a b"));
+        assert_eq!(answers.get(2022, 2, 1), None);
+        assert_eq!(
+            Answers::parse("2019 3 806"),
+            Err("line 1: invalid answer \"2019 3 806\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let report = |part, answer: Option<&str>, outcome| Report {
+            year: 2019,
+            day: 3,
+            part,
+            answer: answer.map(|a| a.to_owned()),
+            outcome,
+        };
+        let reports = [
+            report(1, Some("806"), Outcome::Pass),
+            report(
+                2,
+                Some("1"),
+                Outcome::Fail {
+                    expected: "2".to_owned(),
+                },
+            ),
+        ];
+        let expected = "\
+year  day  part  status    answer
+2019    3     1  pass      806
+2019    3     2  FAIL      1 (expected 2)
+1 passed, 1 failed, 0 missing, 0 without input";
+        assert_eq!(Table(&reports).to_string(), expected);
+    }
+
+    #[test]
+    fn test_known_answers() {
+        let inputs = Inputs::new(DEFAULT_DATA_DIR);
+        let answers = Answers::load(&inputs.root().join(ANSWERS_FILE)).unwrap();
+        let days = DAYS
+            .iter()
+            .filter(|day| inputs.exists(day.year, day.day))
+            .collect::<Vec<_>>();
+        let reports = verify(&days, &inputs, &answers);
+
+        let failed = reports
+            .iter()
+            .filter(|r| r.outcome != Outcome::Pass)
+            .collect::<Vec<_>>();
+        assert!(failed.is_empty(), "\n{}", Table(&reports));
+    }
+}
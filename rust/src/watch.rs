@@ -0,0 +1,244 @@
+use crate::command::CommandError;
+use crate::examples::Examples;
+use crate::input::Inputs;
+use crate::json::Value;
+use crate::output::{Record, Status};
+use crate::registry;
+use crate::scaffold::CRATE_DIR;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The day being worked on and the files that trigger a rerun when they change.
+pub struct Watch {
+    pub crate_dir: PathBuf,
+    pub data_dir: PathBuf,
+    pub year: u16,
+    pub day: u8,
+    pub paths: Vec<PathBuf>,
+}
+
+impl Watch {
+    /// Watches the day's source, its input and its examples.
+    pub fn new(crate_dir: &Path, inputs: &Inputs, year: u16, day: u8) -> Self {
+        let source = crate_dir
+            .join("src")
+            .join(format!("year{}", year))
+            .join(format!("day{:02}.rs", day));
+        Self {
+            crate_dir: crate_dir.to_owned(),
+            data_dir: inputs.root().to_owned(),
+            year,
+            day,
+            paths: vec![
+                source,
+                inputs.path(year, day),
+                Examples::path(inputs, year, day),
+            ],
+        }
+    }
+
+    /// When each watched file was last modified, or `None` for files that don't exist.
+    pub fn snapshot(&self) -> Vec<Option<SystemTime>> {
+        self.paths
+            .iter()
+            .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    /// Reruns every time a watched file changes, until interrupted.
+    pub fn run(&self) -> ! {
+        let mut previous = None;
+        loop {
+            let snapshot = self.snapshot();
+            previous = Some(self.cycle(previous.as_deref()));
+            while self.snapshot() == snapshot {
+                thread::sleep(POLL_INTERVAL);
+            }
+            // editors often write a file in more than one go
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn cargo(&self, args: &[&str]) -> Command {
+        let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+        let mut command = Command::new(cargo);
+        command.args(args).current_dir(&self.crate_dir);
+        command
+    }
+
+    /// Runs the day's tests, its examples and both parts, printing how the answers changed
+    /// since `previous`. The solutions are rebuilt, so cargo does the running.
+    fn cycle(&self, previous: Option<&[Record]>) -> Vec<Record> {
+        let (year, day) = (self.year.to_string(), self.day.to_string());
+        let data_dir = self.data_dir.to_string_lossy();
+        let time = crate::download::now() % 86_400;
+        println!(
+            "--- {} day {} at {:02}:{:02}:{:02} UTC ---",
+            self.year,
+            self.day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        );
+
+        let filter = format!("year{}::day{:02}::", self.year, self.day);
+        match self.cargo(&["test", "--quiet", "--lib", &filter]).output() {
+            Ok(output) if output.status.success() => println!("tests: pass"),
+            Ok(output) => {
+                println!("tests: FAIL");
+                print!("{}", String::from_utf8_lossy(&output.stdout));
+                eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            }
+            Err(e) => {
+                println!("tests: could not run cargo: {}", e);
+                return previous.map(<[_]>::to_vec).unwrap_or_default();
+            }
+        }
+
+        let run = |args: &[&str]| {
+            let mut all = vec!["run", "--quiet", "--"];
+            all.extend_from_slice(args);
+            all.extend(["--data-dir", &data_dir]);
+            self.cargo(&all).output()
+        };
+        if self.paths[2].exists() {
+            if let Ok(output) = run(&["examples", &year, &day]) {
+                let status = if output.status.success() {
+                    "pass"
+                } else {
+                    "FAIL"
+                };
+                println!("examples: {}", status);
+                if !output.status.success() {
+                    print!("{}", String::from_utf8_lossy(&output.stdout));
+                }
+            }
+        }
+
+        let records = run(&["run", &year, &day, "--format", "json"])
+            .map_err(|e| e.to_string())
+            .and_then(|output| {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                Value::parse(stdout.trim())
+                    .and_then(|json| Record::from_json(&json))
+                    .map_err(|_| String::from_utf8_lossy(&output.stderr).into_owned())
+            });
+        match records {
+            Ok(records) => {
+                print!("{}", changes(previous, &records));
+                records
+            }
+            Err(e) => {
+                println!("run failed: {}", e.trim());
+                previous.map(<[_]>::to_vec).unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// One line per part: its answer or error and how long it took, with what it was before when
+/// that's different.
+pub fn changes(previous: Option<&[Record]>, current: &[Record]) -> String {
+    let shown = |record: &Record| match record.status {
+        Status::Ok => record.answer.clone().unwrap_or_default(),
+        _ => format!(
+            "{}: {}",
+            record.status.name(),
+            record.error.as_deref().unwrap_or("")
+        ),
+    };
+    let mut lines = String::new();
+    for record in current {
+        let now = shown(record);
+        let before = previous
+            .and_then(|p| p.iter().find(|r| r.part == record.part))
+            .map(shown);
+        let change = match before {
+            Some(before) if before != now => format!("  (was {})", before),
+            Some(_) => String::new(),
+            None if previous.is_some() => "  (new)".to_owned(),
+            None => String::new(),
+        };
+        let duration = crate::bench::format_duration(record.duration);
+        let _ = writeln!(
+            lines,
+            "part {}: {}  [{}]{}",
+            record.part, now, duration, change
+        );
+    }
+    lines
+}
+
+/// Watches a day from the crate it was built from until the process is stopped.
+pub fn run(year: u16, day: u8, inputs: &Inputs) -> Result<(), CommandError> {
+    registry::find(year, day)?;
+    Watch::new(Path::new(CRATE_DIR), inputs, year, day).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    fn record(part: u8, answer: &str) -> Record {
+        Record {
+            year: 2024,
+            day: 1,
+            part,
+            answer: Some(answer.to_owned()),
+            duration: Duration::from_micros(250),
+            status: Status::Ok,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let first = [record(1, "11")];
+        assert_eq!(changes(None, &first), "part 1: 11  [250.00µs]\n");
+
+        let mut panicked = record(2, "");
+        panicked.answer = None;
+        panicked.status = Status::Panic;
+        panicked.error = Some("not yet implemented".to_owned());
+        let second = [record(1, "11"), panicked];
+        assert_eq!(
+            changes(Some(&first), &second),
+            "part 1: 11  [250.00µs]\npart 2: panic: not yet implemented  [250.00µs]  (new)\n"
+        );
+
+        let third = [record(1, "12"), record(2, "31")];
+        assert_eq!(
+            changes(Some(&second), &third),
+            "part 1: 12  [250.00µs]  (was 11)\n\
+             part 2: 31  [250.00µs]  (was panic: not yet implemented)\n"
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let dir = Scratch::new("watch");
+        let inputs = Inputs::new(&*dir);
+        let watch = Watch::new(&dir, &inputs, 2024, 1);
+        assert_eq!(
+            watch.paths[0],
+            dir.join("src").join("year2024").join("day01.rs")
+        );
+        assert_eq!(watch.snapshot(), [None, None, None]);
+
+        fs::create_dir_all(dir.join("year2024")).unwrap();
+        fs::write(inputs.path(2024, 1), "3   4\n").unwrap();
+        let snapshot = watch.snapshot();
+        assert!(snapshot[1].is_some());
+        assert_eq!(watch.snapshot(), snapshot);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_ne!(watch.snapshot(), snapshot);
+    }
+}
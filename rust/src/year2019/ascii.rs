@@ -0,0 +1,138 @@
+use crate::year2019::vm::{Channels, Intcode, Snapshot, Status};
+
+/// How many instructions [`Ascii::read`] executes before giving up on a program that neither
+/// prints a prompt nor halts.
+const DEFAULT_STEP_LIMIT: usize = 10_000_000;
+
+/// Drives an Intcode program that talks in ASCII text, one line of input at a time.
+pub struct Ascii<'a> {
+    vm: Intcode<'a>,
+    channels: Channels,
+    step_limit: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Reply {
+    pub text: String,
+    /// Outputs that aren't ASCII characters, such as a final numeric answer.
+    pub values: Vec<i64>,
+    pub state: State,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum State {
+    /// The program is waiting for the next line of input.
+    Waiting,
+    Halted,
+    /// The program ran past the step limit without asking for input or halting.
+    Stalled,
+}
+
+impl<'a> Ascii<'a> {
+    pub fn new(memory: &'a mut [i64]) -> Self {
+        Self {
+            vm: Intcode::new(memory),
+            channels: Channels::default(),
+            step_limit: DEFAULT_STEP_LIMIT,
+        }
+    }
+
+    pub fn with_step_limit(mut self, step_limit: usize) -> Self {
+        self.step_limit = step_limit;
+        self
+    }
+
+    /// Runs the program until it asks for input, halts or stalls, and returns what it printed.
+    pub fn read(&mut self) -> Reply {
+        let mut state = State::Stalled;
+        for _ in 0..self.step_limit {
+            if let Some(status) = self.vm.step(&mut self.channels) {
+                state = match status {
+                    Status::Halted => State::Halted,
+                    Status::AwaitingInput => State::Waiting,
+                };
+                break;
+            }
+        }
+
+        let mut text = String::new();
+        let mut values = Vec::new();
+        for value in self.channels.output.drain(..) {
+            match u8::try_from(value) {
+                Ok(byte) if byte.is_ascii() => text.push(byte as char),
+                _ => values.push(value),
+            }
+        }
+
+        Reply {
+            text,
+            values,
+            state,
+        }
+    }
+
+    /// Sends `line` followed by a newline and returns the program's reply.
+    pub fn send(&mut self, line: &str) -> Reply {
+        self.channels
+            .input
+            .extend(line.bytes().chain(Some(b'\n')).map(i64::from));
+        self.read()
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.vm.snapshot()
+    }
+
+    /// Rewinds the program to `snapshot`, discarding any input it hasn't consumed yet.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.vm.restore(snapshot);
+        self.channels = Channels::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads a number, prints "<number>!" and asks again.
+    const ECHO: [i64; 16] = [
+        3, 100, 1006, 100, 15, 4, 100, 104, 33, 104, 10, 1105, 1, 0, 99, 99,
+    ];
+
+    #[test]
+    fn test_send() {
+        let mut memory = ECHO;
+        let mut ascii = Ascii::new(&mut memory);
+        let reply = ascii.send("hi");
+        assert_eq!(reply.text, "h!\ni!\n\n!\n");
+        assert_eq!(reply.state, State::Waiting);
+    }
+
+    #[test]
+    fn test_non_ascii_values() {
+        let mut memory = [104, 72, 104, 1000, 99];
+        let mut ascii = Ascii::new(&mut memory);
+        let reply = ascii.read();
+        assert_eq!(reply.text, "H");
+        assert_eq!(reply.values, [1000]);
+        assert_eq!(reply.state, State::Halted);
+    }
+
+    #[test]
+    fn test_stalled() {
+        let mut memory = [1105, 1, 0];
+        let mut ascii = Ascii::new(&mut memory).with_step_limit(100);
+        assert_eq!(ascii.read().state, State::Stalled);
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut memory = ECHO;
+        let mut ascii = Ascii::new(&mut memory);
+        ascii.read();
+        let snapshot = ascii.snapshot();
+        ascii.send("a");
+        ascii.restore(&snapshot);
+        assert_eq!(ascii.send("b").text, "b!\n\n!\n");
+    }
+}
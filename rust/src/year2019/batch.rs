@@ -0,0 +1,144 @@
+use crate::year2019::vm::Intcode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Runs many independent machines of the same program across threads. Every machine starts
+/// from one shared copy of the program and only copies the memory it writes to.
+pub struct Batch {
+    image: Arc<[i64]>,
+    threads: usize,
+}
+
+impl Batch {
+    pub fn new(program: &[i64]) -> Self {
+        Self {
+            image: Arc::from(program),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Runs `run` on a fresh machine for every job and returns the results in job order.
+    pub fn map<J, R, F>(&self, jobs: &[J], run: F) -> Vec<R>
+    where
+        J: Sync,
+        R: Send,
+        F: Fn(&mut Intcode<'static>, &J) -> R + Sync,
+    {
+        self.map_until(jobs, run, |_| false)
+    }
+
+    /// Like [`Batch::map`], but stops at the first job (in job order) whose result satisfies
+    /// `stop`. Returns the results up to and including that job, which are the same results
+    /// running the jobs one after another would have produced. Jobs after it that haven't
+    /// started yet are skipped.
+    pub fn map_until<J, R, F, P>(&self, jobs: &[J], run: F, stop: P) -> Vec<R>
+    where
+        J: Sync,
+        R: Send,
+        F: Fn(&mut Intcode<'static>, &J) -> R + Sync,
+        P: Fn(&R) -> bool + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let stop_at = AtomicUsize::new(usize::MAX);
+        let results = Mutex::new(Vec::with_capacity(jobs.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(jobs.len()) {
+                scope.spawn(|| loop {
+                    // jobs are claimed in order, so every job before `stop_at` always runs
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= jobs.len() || index > stop_at.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let mut intcode = Intcode::shared(self.image.clone());
+                    let result = run(&mut intcode, &jobs[index]);
+                    if stop(&result) {
+                        stop_at.fetch_min(index, Ordering::SeqCst);
+                    }
+                    results.lock().unwrap().push((index, result));
+                });
+            }
+        });
+
+        let stop_at = stop_at.into_inner();
+        let mut results = results.into_inner().unwrap();
+        results.retain(|(index, _)| *index <= stop_at);
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// The first job (in job order) whose result satisfies `predicate`, along with that result.
+    pub fn find<J, R, F, P>(&self, jobs: &[J], run: F, predicate: P) -> Option<(usize, R)>
+    where
+        J: Sync,
+        R: Send,
+        F: Fn(&mut Intcode<'static>, &J) -> R + Sync,
+        P: Fn(&R) -> bool + Sync,
+    {
+        let mut results = self.map_until(jobs, run, &predicate);
+        let index = results.len().checked_sub(1)?;
+        let result = results.pop()?;
+        predicate(&result).then_some((index, result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year2019::vm::Channels;
+
+    // Outputs its input times three.
+    const TRIPLE: [i64; 9] = [3, 9, 1002, 9, 3, 9, 4, 9, 99];
+
+    fn triple(intcode: &mut Intcode, input: &i64) -> i64 {
+        let mut channels = Channels::new(&[*input]);
+        intcode.run_with(&mut channels);
+        channels.output[0]
+    }
+
+    #[test]
+    fn test_map() {
+        let jobs = (0..100).collect::<Vec<i64>>();
+        let results = Batch::new(&TRIPLE).with_threads(4).map(&jobs, triple);
+        let expected = jobs.iter().map(|x| x * 3).collect::<Vec<_>>();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_map_until() {
+        let jobs = (0..100).collect::<Vec<i64>>();
+        let results = Batch::new(&TRIPLE)
+            .with_threads(4)
+            .map_until(&jobs, triple, |x| *x >= 30);
+        let expected = (0..=10).map(|x| x * 3).collect::<Vec<_>>();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_find() {
+        let jobs = (0..100).collect::<Vec<i64>>();
+        let batch = Batch::new(&TRIPLE).with_threads(3);
+        assert_eq!(batch.find(&jobs, triple, |x| *x == 42), Some((14, 42)));
+        assert_eq!(batch.find(&jobs, triple, |x| *x == 1), None);
+    }
+
+    #[test]
+    fn test_shared_image_is_not_modified() {
+        let program = [1, 0, 0, 0, 99];
+        let batch = Batch::new(&program);
+        let results = batch.map(&[1, 2, 3], |intcode, x| {
+            intcode.poke(1, *x);
+            intcode.run();
+            intcode.peek(0)
+        });
+        assert_eq!(results, [2, 1, 1]);
+        assert_eq!(*batch.image, program);
+    }
+}
@@ -0,0 +1,442 @@
+use super::parser::{BinaryOp, Expr, Function, Statement};
+use super::CompileError;
+use std::collections::HashMap;
+
+const ADD: i64 = 1;
+const MULTIPLY: i64 = 2;
+const INPUT: i64 = 3;
+const OUTPUT: i64 = 4;
+const JUMP_IF_TRUE: i64 = 5;
+const JUMP_IF_FALSE: i64 = 6;
+const LESS_THAN: i64 = 7;
+const EQUALS: i64 = 8;
+const ADJUST_RELATIVE_BASE: i64 = 9;
+const HALT: i64 = 99;
+
+/// Where a value lives at runtime.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Operand {
+    Immediate(i64),
+    /// A slot in the current stack frame, relative to the relative base.
+    Slot(i64),
+    /// A slot in the frame of the function being called, which starts right after the
+    /// current frame.
+    Callee(i64),
+    /// The address of a label.
+    Label(usize),
+}
+
+/// A word of output whose value may only be known once a function or the whole program has
+/// been generated.
+#[derive(Debug, Clone, Copy)]
+enum Word {
+    Value(i64),
+    Label(usize),
+    /// `scale * frame size + offset`, for the function the word belongs to.
+    Frame {
+        scale: i64,
+        offset: i64,
+    },
+}
+
+/// The code generator. Every function gets a stack frame addressed through the relative base:
+///
+/// | slot | contents |
+/// |------|----------|
+/// | 0 | return address |
+/// | 1.. | parameters, then locals, then temporaries |
+///
+/// A caller writes the return address and arguments into the slots just past its own frame,
+/// moves the relative base there and jumps. The callee leaves its return value in slot 1 and
+/// jumps back through slot 0.
+#[derive(Default)]
+pub struct Codegen {
+    words: Vec<Word>,
+    labels: Vec<Option<usize>>,
+    functions: HashMap<String, (usize, usize)>,
+    /// Variables in scope, innermost scope last.
+    scopes: Vec<HashMap<String, i64>>,
+    /// The first word of the function being generated.
+    function_start: usize,
+    next_slot: i64,
+    next_temporary: i64,
+    frame_size: i64,
+}
+
+impl Codegen {
+    pub fn generate(functions: &[Function]) -> Result<Vec<i64>, CompileError> {
+        let mut codegen = Codegen::default();
+
+        for function in functions {
+            if function.name == "input" || function.name == "output" {
+                return Err(CompileError::new(
+                    function.line,
+                    format!("{} is a built-in function", function.name),
+                ));
+            }
+            let label = codegen.label();
+            let entry = (label, function.params.len());
+            if codegen
+                .functions
+                .insert(function.name.clone(), entry)
+                .is_some()
+            {
+                return Err(CompileError::new(
+                    function.line,
+                    format!("function {} is defined twice", function.name),
+                ));
+            }
+        }
+        let main = match codegen.functions.get("main") {
+            Some(&(label, 0)) => label,
+            Some(_) => return Err(CompileError::new(1, "main takes no parameters")),
+            None => return Err(CompileError::new(1, "no main function")),
+        };
+
+        // the stack starts right after the program
+        let stack = codegen.label();
+        let halt = codegen.label();
+        codegen.emit(ADJUST_RELATIVE_BASE, &[Operand::Label(stack)]);
+        codegen.emit(
+            ADD,
+            &[
+                Operand::Label(halt),
+                Operand::Immediate(0),
+                Operand::Slot(0),
+            ],
+        );
+        codegen.jump(main);
+        codegen.place(halt);
+        codegen.emit(HALT, &[]);
+        codegen.finish_function(0);
+
+        for function in functions {
+            codegen.function(function)?;
+        }
+        codegen.place(stack);
+
+        let labels = codegen
+            .labels
+            .iter()
+            .map(|l| l.expect("label was never placed") as i64)
+            .collect::<Vec<_>>();
+        Ok(codegen
+            .words
+            .iter()
+            .map(|word| match word {
+                Word::Value(value) => *value,
+                Word::Label(label) => labels[*label],
+                Word::Frame { .. } => unreachable!("frame sizes are resolved per function"),
+            })
+            .collect())
+    }
+
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.words.len());
+    }
+
+    fn emit(&mut self, opcode: i64, operands: &[Operand]) {
+        let mut instruction = opcode;
+        let mut mode = 100;
+        for operand in operands {
+            instruction += mode
+                * match operand {
+                    Operand::Immediate(_) | Operand::Label(_) => 1,
+                    Operand::Slot(_) | Operand::Callee(_) => 2,
+                };
+            mode *= 10;
+        }
+
+        self.words.push(Word::Value(instruction));
+        for operand in operands {
+            self.words.push(match *operand {
+                Operand::Immediate(value) | Operand::Slot(value) => Word::Value(value),
+                Operand::Callee(offset) => Word::Frame { scale: 1, offset },
+                Operand::Label(label) => Word::Label(label),
+            });
+        }
+    }
+
+    fn jump(&mut self, label: usize) {
+        self.emit(
+            JUMP_IF_TRUE,
+            &[Operand::Immediate(1), Operand::Label(label)],
+        );
+    }
+
+    fn copy(&mut self, from: Operand, to: Operand) {
+        self.emit(ADD, &[from, Operand::Immediate(0), to]);
+    }
+
+    /// Replaces the frame size placeholders in the function that has just been generated.
+    fn finish_function(&mut self, frame_size: i64) {
+        for word in &mut self.words[self.function_start..] {
+            if let Word::Frame { scale, offset } = *word {
+                *word = Word::Value(scale * frame_size + offset);
+            }
+        }
+        self.function_start = self.words.len();
+    }
+
+    fn function(&mut self, function: &Function) -> Result<(), CompileError> {
+        let (label, _) = self.functions[&function.name];
+        self.place(label);
+
+        self.scopes = vec![HashMap::new()];
+        self.next_slot = 1;
+        self.frame_size = 2;
+        for param in &function.params {
+            self.declare(param, function.line)?;
+        }
+
+        self.block(&function.body)?;
+        self.ret(Operand::Immediate(0));
+
+        let frame_size = self.frame_size;
+        self.finish_function(frame_size);
+        Ok(())
+    }
+
+    fn declare(&mut self, name: &str, line: usize) -> Result<i64, CompileError> {
+        let scope = self.scopes.last_mut().unwrap();
+        if scope.contains_key(name) {
+            return Err(CompileError::new(
+                line,
+                format!("{} is already declared in this scope", name),
+            ));
+        }
+        let slot = self.next_slot;
+        scope.insert(name.to_owned(), slot);
+        self.next_slot += 1;
+        self.frame_size = self.frame_size.max(self.next_slot);
+        Ok(slot)
+    }
+
+    fn variable(&self, name: &str, line: usize) -> Result<Operand, CompileError> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .map(|slot| Operand::Slot(*slot))
+            .ok_or_else(|| CompileError::new(line, format!("undefined variable {}", name)))
+    }
+
+    fn temporary(&mut self) -> Operand {
+        let slot = self.next_slot + self.next_temporary;
+        self.next_temporary += 1;
+        self.frame_size = self.frame_size.max(slot + 1);
+        Operand::Slot(slot)
+    }
+
+    fn block(&mut self, statements: &[Statement]) -> Result<(), CompileError> {
+        self.scopes.push(HashMap::new());
+        for statement in statements {
+            self.statement(statement)?;
+        }
+        // locals are never reused, so the slots of the scope stay allocated
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<(), CompileError> {
+        self.next_temporary = 0;
+        match statement {
+            Statement::Let(name, value, line) => {
+                let value = self.expr(value)?;
+                let slot = self.declare(name, *line)?;
+                self.copy(value, Operand::Slot(slot));
+            }
+            Statement::Assign(name, value, line) => {
+                let variable = self.variable(name, *line)?;
+                let value = self.expr(value)?;
+                self.copy(value, variable);
+            }
+            Statement::If(condition, then, otherwise) => {
+                let otherwise_label = self.label();
+                let end = self.label();
+                let condition = self.expr(condition)?;
+                self.emit(JUMP_IF_FALSE, &[condition, Operand::Label(otherwise_label)]);
+                self.block(then)?;
+                self.jump(end);
+                self.place(otherwise_label);
+                self.block(otherwise)?;
+                self.place(end);
+            }
+            Statement::While(condition, body) => {
+                let start = self.label();
+                let end = self.label();
+                self.place(start);
+                let condition = self.expr(condition)?;
+                self.emit(JUMP_IF_FALSE, &[condition, Operand::Label(end)]);
+                self.block(body)?;
+                self.jump(start);
+                self.place(end);
+            }
+            Statement::Output(value) => {
+                let value = self.expr(value)?;
+                self.emit(OUTPUT, &[value]);
+            }
+            Statement::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expr(value)?,
+                    None => Operand::Immediate(0),
+                };
+                self.ret(value);
+            }
+            Statement::Expr(value) => {
+                self.expr(value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn ret(&mut self, value: Operand) {
+        self.copy(value, Operand::Slot(1));
+        self.emit(JUMP_IF_FALSE, &[Operand::Immediate(0), Operand::Slot(0)]);
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Operand, CompileError> {
+        let operand = match expr {
+            Expr::Number(n) => Operand::Immediate(*n),
+            Expr::Variable(name, line) => self.variable(name, *line)?,
+            Expr::Input => {
+                let result = self.temporary();
+                self.emit(INPUT, &[result]);
+                result
+            }
+            Expr::Call(name, args, line) => self.call(name, args, *line)?,
+            Expr::Negate(value) => {
+                let value = self.expr(value)?;
+                let result = self.temporary();
+                self.emit(MULTIPLY, &[value, Operand::Immediate(-1), result]);
+                result
+            }
+            Expr::Not(value) => {
+                let value = self.expr(value)?;
+                let result = self.temporary();
+                self.emit(EQUALS, &[value, Operand::Immediate(0), result]);
+                result
+            }
+            Expr::Binary(op @ (BinaryOp::And | BinaryOp::Or), left, right) => {
+                self.short_circuit(*op, left, right)?
+            }
+            Expr::Binary(op, left, right) => {
+                let left = self.expr(left)?;
+                let right = self.expr(right)?;
+                self.binary(*op, left, right)
+            }
+        };
+        Ok(operand)
+    }
+
+    fn binary(&mut self, op: BinaryOp, left: Operand, right: Operand) -> Operand {
+        let result = self.temporary();
+        match op {
+            BinaryOp::Add => self.emit(ADD, &[left, right, result]),
+            BinaryOp::Subtract => {
+                self.emit(MULTIPLY, &[right, Operand::Immediate(-1), result]);
+                self.emit(ADD, &[left, result, result]);
+            }
+            BinaryOp::Multiply => self.emit(MULTIPLY, &[left, right, result]),
+            BinaryOp::Equal => self.emit(EQUALS, &[left, right, result]),
+            BinaryOp::NotEqual => {
+                self.emit(EQUALS, &[left, right, result]);
+                self.emit(EQUALS, &[result, Operand::Immediate(0), result]);
+            }
+            BinaryOp::Less => self.emit(LESS_THAN, &[left, right, result]),
+            BinaryOp::Greater => self.emit(LESS_THAN, &[right, left, result]),
+            BinaryOp::LessEqual => {
+                self.emit(LESS_THAN, &[right, left, result]);
+                self.emit(EQUALS, &[result, Operand::Immediate(0), result]);
+            }
+            BinaryOp::GreaterEqual => {
+                self.emit(LESS_THAN, &[left, right, result]);
+                self.emit(EQUALS, &[result, Operand::Immediate(0), result]);
+            }
+            BinaryOp::And | BinaryOp::Or => unreachable!("handled by short_circuit"),
+        }
+        result
+    }
+
+    /// `&&` and `||` only evaluate their right-hand side when it decides the result, which is
+    /// always 0 or 1.
+    fn short_circuit(
+        &mut self,
+        op: BinaryOp,
+        left: &Expr,
+        right: &Expr,
+    ) -> Result<Operand, CompileError> {
+        let end = self.label();
+        let result = self.temporary();
+        let left = self.expr(left)?;
+        let (short, jump) = match op {
+            BinaryOp::And => (0, JUMP_IF_FALSE),
+            _ => (1, JUMP_IF_TRUE),
+        };
+
+        self.copy(Operand::Immediate(short), result);
+        self.emit(jump, &[left, Operand::Label(end)]);
+        let right = self.expr(right)?;
+        self.emit(EQUALS, &[right, Operand::Immediate(0), result]);
+        self.emit(EQUALS, &[result, Operand::Immediate(0), result]);
+        self.place(end);
+        Ok(result)
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr], line: usize) -> Result<Operand, CompileError> {
+        let &(label, arity) = self
+            .functions
+            .get(name)
+            .ok_or_else(|| CompileError::new(line, format!("undefined function {}", name)))?;
+        if args.len() != arity {
+            return Err(CompileError::new(
+                line,
+                format!(
+                    "{} takes {} arguments but {} were given",
+                    name,
+                    arity,
+                    args.len()
+                ),
+            ));
+        }
+
+        // evaluate every argument before filling in the callee's frame, since evaluating one
+        // may call another function that reuses it
+        let mut values = Vec::new();
+        for arg in args {
+            values.push(self.expr(arg)?);
+        }
+        for (i, value) in values.into_iter().enumerate() {
+            self.copy(value, Operand::Callee(i as i64 + 1));
+        }
+
+        let back = self.label();
+        self.emit(
+            ADD,
+            &[
+                Operand::Label(back),
+                Operand::Immediate(0),
+                Operand::Callee(0),
+            ],
+        );
+        self.adjust_relative_base(1);
+        self.jump(label);
+        self.place(back);
+        self.adjust_relative_base(-1);
+
+        let result = self.temporary();
+        self.copy(Operand::Callee(1), result);
+        Ok(result)
+    }
+
+    /// Moves the relative base `scale` frames forwards or backwards.
+    fn adjust_relative_base(&mut self, scale: i64) {
+        self.words.push(Word::Value(100 + ADJUST_RELATIVE_BASE));
+        self.words.push(Word::Frame { scale, offset: 0 });
+    }
+}
@@ -0,0 +1,153 @@
+use super::CompileError;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Token {
+    Number(i64),
+    Ident(String),
+    Fn,
+    Let,
+    If,
+    Else,
+    While,
+    Return,
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Semicolon,
+    Assign,
+    Plus,
+    Minus,
+    Star,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Not,
+    And,
+    Or,
+    Eof,
+}
+
+/// A token and the line it starts on.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Spanned {
+    pub token: Token,
+    pub line: usize,
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>, CompileError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '0'..='9' => {
+                let mut digits = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                }
+                let number = digits.parse().map_err(|_| {
+                    CompileError::new(line, format!("number too large: {}", digits))
+                })?;
+                Token::Number(number)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                match ident.as_str() {
+                    "fn" => Token::Fn,
+                    "let" => Token::Let,
+                    "if" => Token::If,
+                    "else" => Token::Else,
+                    "while" => Token::While,
+                    "return" => Token::Return,
+                    _ => Token::Ident(ident),
+                }
+            }
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Equal,
+            '=' => Token::Assign,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::NotEqual,
+            '!' => Token::Not,
+            '<' if chars.next_if_eq(&'=').is_some() => Token::LessEqual,
+            '<' => Token::Less,
+            '>' if chars.next_if_eq(&'=').is_some() => Token::GreaterEqual,
+            '>' => Token::Greater,
+            '&' if chars.next_if_eq(&'&').is_some() => Token::And,
+            '|' if chars.next_if_eq(&'|').is_some() => Token::Or,
+            c => {
+                return Err(CompileError::new(
+                    line,
+                    format!("unexpected character {:?}", c),
+                ))
+            }
+        };
+        tokens.push(Spanned { token, line });
+    }
+
+    tokens.push(Spanned {
+        token: Token::Eof,
+        line,
+    });
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("let x = 10; // ten\nx >= -2 && !y")
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.token, t.line))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (Token::Let, 1),
+            (Token::Ident("x".to_owned()), 1),
+            (Token::Assign, 1),
+            (Token::Number(10), 1),
+            (Token::Semicolon, 1),
+            (Token::Ident("x".to_owned()), 2),
+            (Token::GreaterEqual, 2),
+            (Token::Minus, 2),
+            (Token::Number(2), 2),
+            (Token::And, 2),
+            (Token::Not, 2),
+            (Token::Ident("y".to_owned()), 2),
+            (Token::Eof, 2),
+        ];
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_tokenize_unexpected_character() {
+        let error = tokenize("let x = 1;\nx = x / 2;").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: unexpected character '/'");
+    }
+}
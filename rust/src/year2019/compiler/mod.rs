@@ -0,0 +1,224 @@
+//! A small language that compiles to Intcode.
+//!
+//! ```text
+//! // prints the first `n` Fibonacci numbers
+//! fn main() {
+//!     let n = input();
+//!     let a = 0;
+//!     let b = 1;
+//!     while n > 0 {
+//!         output(a);
+//!         let next = a + b;
+//!         a = b;
+//!         b = next;
+//!         n = n - 1;
+//!     }
+//! }
+//! ```
+//!
+//! Programs are a list of functions, starting at `main`. Values are 64-bit integers, with `+`,
+//! `-`, `*`, comparisons, `!`, `&&` and `||` (the last four treat zero as false and produce 0 or
+//! 1). `input()` reads a value and `output(x);` writes one. Functions take any number of
+//! arguments, may recurse and return 0 unless they `return` something else.
+
+mod codegen;
+mod lexer;
+mod parser;
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompileError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl CompileError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Compiles `source` into an Intcode program.
+pub fn compile(source: &str) -> Result<Vec<i64>, CompileError> {
+    let tokens = lexer::tokenize(source)?;
+    let functions = parser::parse(&tokens)?;
+    codegen::Codegen::generate(&functions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year2019::vm::{Channels, Intcode, Status};
+
+    fn run(source: &str, input: &[i64]) -> Vec<i64> {
+        let mut memory = compile(source).unwrap();
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::new(input);
+        assert_eq!(intcode.run_with(&mut channels), Status::Halted);
+        channels.output
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let source = "fn main() { output(1 + 2 * 3 - -4); output((1 + 2) * 3 - 10); }";
+        assert_eq!(run(source, &[]), [11, -1]);
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let source = "
+            fn main() {
+                let a = input();
+                let b = input();
+                output(a < b);
+                output(a <= b);
+                output(a > b);
+                output(a >= b);
+                output(a == b);
+                output(a != b);
+                output(!a);
+            }";
+        assert_eq!(run(source, &[1, 2]), [1, 1, 0, 0, 0, 1, 0]);
+        assert_eq!(run(source, &[2, 2]), [0, 1, 0, 1, 1, 0, 0]);
+        assert_eq!(run(source, &[0, -1]), [0, 0, 1, 1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_short_circuit() {
+        let source = "
+            fn loud(x) {
+                output(x);
+                return x;
+            }
+
+            fn main() {
+                output(loud(0) && loud(2));
+                output(loud(3) || loud(4));
+                output(loud(5) && loud(6));
+            }";
+        assert_eq!(run(source, &[]), [0, 0, 3, 1, 5, 6, 1]);
+    }
+
+    #[test]
+    fn test_if_else() {
+        let source = "
+            fn main() {
+                let x = input();
+                if x < 0 {
+                    output(-1);
+                } else if x == 0 {
+                    output(0);
+                } else {
+                    output(1);
+                }
+            }";
+        assert_eq!(run(source, &[-5]), [-1]);
+        assert_eq!(run(source, &[0]), [0]);
+        assert_eq!(run(source, &[5]), [1]);
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let source = "
+            fn main() {
+                let n = input();
+                let a = 0;
+                let b = 1;
+                while n > 0 {
+                    output(a);
+                    let next = a + b;
+                    a = b;
+                    b = next;
+                    n = n - 1;
+                }
+            }";
+        assert_eq!(run(source, &[10]), [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn test_recursion() {
+        let source = "
+            fn factorial(n) {
+                if n <= 1 {
+                    return 1;
+                }
+                return n * factorial(n - 1);
+            }
+
+            fn ackermann(m, n) {
+                if m == 0 {
+                    return n + 1;
+                }
+                if n == 0 {
+                    return ackermann(m - 1, 1);
+                }
+                return ackermann(m - 1, ackermann(m, n - 1));
+            }
+
+            fn main() {
+                output(factorial(input()));
+                output(ackermann(2, 3));
+            }";
+        assert_eq!(run(source, &[20]), [2432902008176640000, 9]);
+    }
+
+    #[test]
+    fn test_nested_calls_in_arguments() {
+        let source = "
+            fn sub(a, b) { return a - b; }
+            fn main() { output(sub(sub(10, 3), sub(4, sub(2, 1)))); }";
+        assert_eq!(run(source, &[]), [4]);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let source = "
+            fn main() {
+                let x = 1;
+                if 1 {
+                    let x = 2;
+                    output(x);
+                }
+                output(x);
+            }";
+        assert_eq!(run(source, &[]), [2, 1]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source| compile(source).unwrap_err().to_string();
+        assert_eq!(error("fn f() {}"), "line 1: no main function");
+        assert_eq!(
+            error("fn main() {\n  output(x);\n}"),
+            "line 2: undefined variable x"
+        );
+        assert_eq!(
+            error("fn main() {\n  f(1);\n}"),
+            "line 2: undefined function f"
+        );
+        assert_eq!(
+            error("fn f(a) {}\nfn main() {\n  f();\n}"),
+            "line 3: f takes 1 arguments but 0 were given"
+        );
+        assert_eq!(
+            error("fn main() {\n  let x = 1;\n  let x = 2;\n}"),
+            "line 3: x is already declared in this scope"
+        );
+        assert_eq!(
+            error("fn main() {}\nfn main() {}"),
+            "line 2: function main is defined twice"
+        );
+    }
+}
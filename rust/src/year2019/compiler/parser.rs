@@ -0,0 +1,368 @@
+use super::lexer::{Spanned, Token};
+use super::CompileError;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Statement>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Statement {
+    Let(String, Expr, usize),
+    Assign(String, Expr, usize),
+    If(Expr, Vec<Statement>, Vec<Statement>),
+    While(Expr, Vec<Statement>),
+    Output(Expr),
+    Return(Option<Expr>),
+    Expr(Expr),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Expr {
+    Number(i64),
+    Variable(String, usize),
+    Input,
+    Call(String, Vec<Expr>, usize),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+
+pub fn parse(tokens: &[Spanned]) -> Result<Vec<Function>, CompileError> {
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let mut functions = Vec::new();
+    while parser.peek() != &Token::Eof {
+        functions.push(parser.function()?);
+    }
+    Ok(functions)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Spanned],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].token
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.position].line
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].token.clone();
+        if token != Token::Eof {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == token {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), CompileError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{:?}", token)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, CompileError> {
+        match self.peek().clone() {
+            Token::Ident(name) => {
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> CompileError {
+        CompileError::new(
+            self.line(),
+            format!("expected {}, found {:?}", expected, self.peek()),
+        )
+    }
+
+    fn function(&mut self) -> Result<Function, CompileError> {
+        let line = self.line();
+        self.expect(Token::Fn)?;
+        let name = self.ident()?;
+        self.expect(Token::LeftParen)?;
+        let mut params = Vec::new();
+        if !self.eat(&Token::RightParen) {
+            loop {
+                params.push(self.ident()?);
+                if self.eat(&Token::RightParen) {
+                    break;
+                }
+                self.expect(Token::Comma)?;
+            }
+        }
+        let body = self.block()?;
+        Ok(Function {
+            name,
+            params,
+            body,
+            line,
+        })
+    }
+
+    fn block(&mut self) -> Result<Vec<Statement>, CompileError> {
+        self.expect(Token::LeftBrace)?;
+        let mut statements = Vec::new();
+        while !self.eat(&Token::RightBrace) {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement, CompileError> {
+        let line = self.line();
+        let statement = match self.peek() {
+            Token::Let => {
+                self.advance();
+                let name = self.ident()?;
+                self.expect(Token::Assign)?;
+                Statement::Let(name, self.expr()?, line)
+            }
+            Token::If => return self.if_statement(),
+            Token::While => {
+                self.advance();
+                let condition = self.expr()?;
+                return Ok(Statement::While(condition, self.block()?));
+            }
+            Token::Return => {
+                self.advance();
+                if self.peek() == &Token::Semicolon {
+                    Statement::Return(None)
+                } else {
+                    Statement::Return(Some(self.expr()?))
+                }
+            }
+            Token::Ident(name) if name == "output" => {
+                self.advance();
+                self.expect(Token::LeftParen)?;
+                let value = self.expr()?;
+                self.expect(Token::RightParen)?;
+                Statement::Output(value)
+            }
+            Token::Ident(name) if self.tokens[self.position + 1].token == Token::Assign => {
+                let name = name.clone();
+                self.advance();
+                self.advance();
+                Statement::Assign(name, self.expr()?, line)
+            }
+            _ => Statement::Expr(self.expr()?),
+        };
+        self.expect(Token::Semicolon)?;
+        Ok(statement)
+    }
+
+    fn if_statement(&mut self) -> Result<Statement, CompileError> {
+        self.expect(Token::If)?;
+        let condition = self.expr()?;
+        let then = self.block()?;
+        let otherwise = if !self.eat(&Token::Else) {
+            Vec::new()
+        } else if self.peek() == &Token::If {
+            vec![self.if_statement()?]
+        } else {
+            self.block()?
+        };
+        Ok(Statement::If(condition, then, otherwise))
+    }
+
+    fn expr(&mut self) -> Result<Expr, CompileError> {
+        self.binary(0)
+    }
+
+    /// Parses binary operators from the loosest binding `level` of [`PRECEDENCE`] inwards.
+    fn binary(&mut self, level: usize) -> Result<Expr, CompileError> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+
+        let mut left = self.binary(level + 1)?;
+        while let Some((_, op)) = operators.iter().find(|(t, _)| t == self.peek()) {
+            self.advance();
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(*op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        if self.eat(&Token::Minus) {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else if self.eat(&Token::Not) {
+            Ok(Expr::Not(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        let line = self.line();
+        match self.advance() {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::LeftParen => {
+                let expr = self.expr()?;
+                self.expect(Token::RightParen)?;
+                Ok(expr)
+            }
+            Token::Ident(name) if self.eat(&Token::LeftParen) => {
+                let mut args = Vec::new();
+                if !self.eat(&Token::RightParen) {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.eat(&Token::RightParen) {
+                            break;
+                        }
+                        self.expect(Token::Comma)?;
+                    }
+                }
+                if name == "input" && args.is_empty() {
+                    Ok(Expr::Input)
+                } else {
+                    Ok(Expr::Call(name, args, line))
+                }
+            }
+            Token::Ident(name) => Ok(Expr::Variable(name, line)),
+            _ => {
+                self.position -= 1;
+                Err(self.unexpected("expression"))
+            }
+        }
+    }
+}
+
+/// Binary operators from loosest to tightest binding.
+const PRECEDENCE: [&[(Token, BinaryOp)]; 6] = [
+    &[(Token::Or, BinaryOp::Or)],
+    &[(Token::And, BinaryOp::And)],
+    &[
+        (Token::Equal, BinaryOp::Equal),
+        (Token::NotEqual, BinaryOp::NotEqual),
+    ],
+    &[
+        (Token::Less, BinaryOp::Less),
+        (Token::LessEqual, BinaryOp::LessEqual),
+        (Token::Greater, BinaryOp::Greater),
+        (Token::GreaterEqual, BinaryOp::GreaterEqual),
+    ],
+    &[
+        (Token::Plus, BinaryOp::Add),
+        (Token::Minus, BinaryOp::Subtract),
+    ],
+    &[(Token::Star, BinaryOp::Multiply)],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::super::lexer::tokenize;
+    use super::*;
+
+    fn parse_expr(source: &str) -> Expr {
+        let tokens = tokenize(&format!("fn main() {{ {}; }}", source)).unwrap();
+        let mut functions = parse(&tokens).unwrap();
+        match functions.remove(0).body.remove(0) {
+            Statement::Expr(expr) => expr,
+            s => panic!("not an expression: {:?}", s),
+        }
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expr = parse_expr("1 + 2 * 3 < 4 || a");
+        let expected = Expr::Binary(
+            BinaryOp::Or,
+            Box::new(Expr::Binary(
+                BinaryOp::Less,
+                Box::new(Expr::Binary(
+                    BinaryOp::Add,
+                    Box::new(Expr::Number(1)),
+                    Box::new(Expr::Binary(
+                        BinaryOp::Multiply,
+                        Box::new(Expr::Number(2)),
+                        Box::new(Expr::Number(3)),
+                    )),
+                )),
+                Box::new(Expr::Number(4)),
+            )),
+            Box::new(Expr::Variable("a".to_owned(), 1)),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_left_associative() {
+        let expr = parse_expr("1 - 2 - 3");
+        let expected = Expr::Binary(
+            BinaryOp::Subtract,
+            Box::new(Expr::Binary(
+                BinaryOp::Subtract,
+                Box::new(Expr::Number(1)),
+                Box::new(Expr::Number(2)),
+            )),
+            Box::new(Expr::Number(3)),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_else_if() {
+        let tokens = tokenize("fn main() { if a { } else if b { output(1); } else { } }").unwrap();
+        let functions = parse(&tokens).unwrap();
+        let expected = Statement::If(
+            Expr::Variable("a".to_owned(), 1),
+            vec![],
+            vec![Statement::If(
+                Expr::Variable("b".to_owned(), 1),
+                vec![Statement::Output(Expr::Number(1))],
+                vec![],
+            )],
+        );
+        assert_eq!(functions[0].body, vec![expected]);
+    }
+
+    #[test]
+    fn test_missing_semicolon() {
+        let tokens = tokenize("fn main() {\n  let x = 1\n}").unwrap();
+        let error = parse(&tokens).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: expected Semicolon, found RightBrace"
+        );
+    }
+}
@@ -1,37 +1,54 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
-use std::iter::Flatten;
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run_part1() {
-    let sum: i32 = read_file()
-        .flat_map(|line| line.parse::<i32>())
-        .map(|mass| get_fuel_for_mass(mass))
-        .sum();
+/// The Tyranny of the Rocket Equation: the fuel for every module, first for its mass alone and
+/// then for the mass of the fuel as well.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2019::day01::Day01;
+///
+/// let masses = Day01::parse("12\n1969")?;
+/// assert_eq!(Day01::part1(&masses), 2 + 654);
+/// assert_eq!(Day01::part2(&masses), 2 + 966);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day01;
 
-    println!("{}", sum);
-}
-
-pub fn run_part2() {
-    let sum: i32 = read_file()
-        .flat_map(|line| line.parse::<i32>())
-        .map(|mass| get_fuel_for_module(mass))
-        .sum();
+impl Solution for Day01 {
+    type Input<'a> = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-    println!("{}", sum);
-}
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        data.lines().map(|line| error::number(data, line)).collect()
+    }
 
-fn read_file() -> Flatten<Lines<BufReader<File>>> {
-    let filepath = "/Users/hanhossain/Developer/advent-of-code/rust/data/year2019/day01.txt";
+    fn part1(input: &Self::Input<'_>) -> i32 {
+        input.iter().copied().map(get_fuel_for_mass).sum()
+    }
 
-    let file = File::open(filepath).unwrap();
-    BufReader::new(file).lines().flatten()
+    fn part2(input: &Self::Input<'_>) -> i32 {
+        input.iter().copied().map(get_fuel_for_module).sum()
+    }
 }
 
-fn get_fuel_for_mass(mass: i32) -> i32 {
+/// The fuel to launch `mass`: a third of it, rounded down, less 2. Masses under 6 need less
+/// than no fuel.
+pub fn get_fuel_for_mass(mass: i32) -> i32 {
     mass / 3 - 2
 }
 
-fn get_fuel_for_module(mass: i32) -> i32 {
+/// The fuel to launch `mass`, plus the fuel for that fuel, and so on until more fuel would need
+/// none.
+///
+/// ```
+/// use aoc::year2019::day01::get_fuel_for_module;
+///
+/// assert_eq!(get_fuel_for_module(1969), 966);
+/// assert_eq!(get_fuel_for_module(4), 0);
+/// ```
+pub fn get_fuel_for_module(mass: i32) -> i32 {
     let fuel = get_fuel_for_mass(mass);
 
     if fuel > 0 {
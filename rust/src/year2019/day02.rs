@@ -1,33 +1,90 @@
-use crate::year2019::vm::Intcode;
+use crate::anonymise::{self, Rng};
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::year2019::batch::Batch;
+use crate::year2019::vm::{parse_program, Intcode};
 
-const DATA: &str = include_str!("../../data/year2019/day02.txt");
+/// 1202 Program Alarm: what an Intcode program leaves at address 0 with the noun 12 and verb 2,
+/// and which noun and verb make it leave 19690720.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2019::day02::Day02;
+///
+/// let program = Day02::parse("1,0,0,0,99,0,0,0,0,0,0,0,7")?;
+/// assert_eq!(Day02::part1(&program), 7 + 2);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day02;
 
-pub fn run_part1() {
-    let result = run(12, 2);
-    println!("{}", result);
-}
+impl Solution for Day02 {
+    type Input<'a> = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = Result<i64, ParseError>;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_program(data)
+    }
+
+    fn part1(program: &Self::Input<'_>) -> i64 {
+        run(program, 12, 2)
+    }
+
+    fn part2(program: &Self::Input<'_>) -> Result<i64, ParseError> {
+        let inputs = (0..100)
+            .flat_map(|noun| (0..100).map(move |verb| (noun, verb)))
+            .collect::<Vec<_>>();
+        let batch = Batch::new(program);
+        let (index, _) = batch
+            .find(
+                &inputs,
+                |vm, &(noun, verb)| run_patched(vm, noun, verb),
+                |output| *output == 19690720,
+            )
+            .ok_or_else(|| ParseError::whole("no noun and verb make the program leave 19690720"))?;
 
-pub fn run_part2() {
-    for noun in 0..100 {
-        for verb in 0..100 {
-            if run(noun, verb) == 19690720 {
-                let result = 100 * noun + verb;
-                println!("{}", result);
-                break;
-            }
-        }
+        let (noun, verb) = inputs[index];
+        Ok(100 * noun + verb)
+    }
+
+    fn synthesise(_: &str, _: &mut Rng) -> Result<String, &'static str> {
+        Err(anonymise::OPAQUE)
     }
 }
 
-fn run(noun: i32, verb: i32) -> i32 {
-    let mut memory = read_file();
-    memory[1] = noun;
-    memory[2] = verb;
+/// Runs `program` with `noun` at address 1 and `verb` at address 2, and gives what it leaves at
+/// address 0.
+///
+/// ```
+/// use aoc::year2019::day02::run;
+///
+/// assert_eq!(run(&[1, 0, 0, 0, 99, 20, 22], 5, 6), 20 + 22);
+/// ```
+pub fn run(program: &[i64], noun: i64, verb: i64) -> i64 {
+    let mut memory = program.to_vec();
     let mut vm = Intcode::new(&mut memory);
+    run_patched(&mut vm, noun, verb)
+}
+
+fn run_patched(vm: &mut Intcode, noun: i64, verb: i64) -> i64 {
+    vm.poke(1, noun);
+    vm.poke(2, verb);
     vm.run();
-    memory[0]
+    vm.peek(0)
 }
 
-fn read_file() -> Vec<i32> {
-    DATA.split(",").flat_map(|x| x.parse::<i32>()).collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_noun_and_verb() {
+        let program = Day02::parse("1,0,0,0,99").unwrap();
+        assert_eq!(
+            Day02::part2(&program),
+            Err(ParseError::whole(
+                "no noun and verb make the program leave 19690720"
+            ))
+        );
+    }
 }
@@ -1,33 +1,75 @@
+use crate::anonymise::{self, Rng};
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 use std::collections::{HashMap, HashSet};
 
-const DATA: &str = include_str!("../../data/year2019/day03.txt");
+/// Crossed Wires: where two wires traced out from the same port cross, closest to the port and
+/// then soonest along both wires.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2019::day03::Day03;
+///
+/// let wires = Day03::parse("R8,U5,L5,D3\nU7,R6,D4,L4")?;
+/// assert_eq!(Day03::part1(&wires), 6);
+/// assert_eq!(Day03::part2(&wires), 30);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day03;
 
-pub fn run_part1() {
-    let distance = calculate_distance(DATA);
-    println!("Distance: {}", distance);
-}
+impl Solution for Day03 {
+    type Input<'a> = Vec<Trail>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> i32 {
+        calculate_distance(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i32 {
+        calculate_signal_delay(input)
+    }
 
-pub fn run_part2() {
-    let signal_delay = calculate_signal_delay(DATA);
-    println!("Signal delay: {}", signal_delay);
+    /// Random wires with the same turns, made up again until they cross.
+    fn synthesise(data: &str, rng: &mut Rng) -> Result<String, &'static str> {
+        (0..100)
+            .map(|_| anonymise::synthesise(data, rng))
+            .find(|made| parse(made).is_ok())
+            .ok_or("the made up wires never cross")
+    }
 }
 
-fn parse(s: &str) -> Vec<Trail> {
-    s.lines()
+fn parse(s: &str) -> Result<Vec<Trail>, ParseError> {
+    let trails = s
+        .lines()
         .map(|l| {
             let segments = l
                 .split(",")
-                .map(|x| Segment::parse(x))
-                .collect::<Vec<Segment>>();
+                .map(|segment| Segment::parse(s, segment))
+                .collect::<Result<Vec<Segment>, _>>()?;
             let mut trail = Trail::new();
             trail.update(&segments);
-            trail
+            Ok(trail)
         })
-        .collect()
+        .collect::<Result<Vec<_>, _>>()?;
+    if trails.len() < 2 {
+        let last = s.lines().last().unwrap_or(s);
+        return Err(ParseError::after(s, last, "expected a second wire"));
+    }
+    let (first, second) = (&trails[0].coordinates, &trails[1].coordinates);
+    if !first.keys().any(|point| second.contains_key(point)) {
+        let second = s.lines().nth(1).unwrap();
+        return Err(ParseError::after(s, second, "the wires never cross"));
+    }
+    Ok(trails)
 }
 
-fn calculate_distance(s: &str) -> i32 {
-    let trails = parse(s);
+/// The Manhattan distance from the port to the closest crossing of the first two wires.
+pub fn calculate_distance(trails: &[Trail]) -> i32 {
     let trail1 = &trails[0];
     let trail2 = &trails[1];
 
@@ -41,8 +83,8 @@ fn calculate_distance(s: &str) -> i32 {
         .unwrap()
 }
 
-fn calculate_signal_delay(s: &str) -> i32 {
-    let trails = parse(s);
+/// The fewest steps the first two wires take, added together, to reach a crossing.
+pub fn calculate_signal_delay(trails: &[Trail]) -> i32 {
     let trail1 = &trails[0];
     let trail2 = &trails[1];
 
@@ -67,31 +109,40 @@ struct Segment {
 }
 
 impl Segment {
-    fn parse(s: &str) -> Self {
-        match (&s[..1], &s[1..]) {
-            ("U", x) => Segment {
+    /// Reads `s`, a segment in `data`, like `R75`.
+    fn parse(data: &str, s: &str) -> Result<Self, ParseError> {
+        let Some(direction) = s.get(..1) else {
+            return Err(ParseError::at(data, s, "expected a direction"));
+        };
+        let distance = || error::number::<i32>(data, &s[1..]);
+        Ok(match direction {
+            "U" => Segment {
                 x: 0,
-                y: x.parse::<i32>().unwrap(),
+                y: distance()?,
             },
-            ("D", x) => Segment {
+            "D" => Segment {
                 x: 0,
-                y: -x.parse::<i32>().unwrap(),
+                y: -distance()?,
             },
-            ("L", x) => Segment {
-                x: -x.parse::<i32>().unwrap(),
+            "L" => Segment {
+                x: -distance()?,
                 y: 0,
             },
-            ("R", x) => Segment {
-                x: x.parse::<i32>().unwrap(),
+            "R" => Segment {
+                x: distance()?,
                 y: 0,
             },
-            _ => unreachable!(),
-        }
+            _ => {
+                let message = format!("expected U, D, L or R, found {:?}", direction);
+                return Err(ParseError::at(data, s, message));
+            }
+        })
     }
 }
 
+/// Every point a wire passes through, with the steps it takes to first get there.
 #[derive(Debug)]
-struct Trail {
+pub struct Trail {
     last_x: i32,
     last_y: i32,
     coordinates: HashMap<(i32, i32), i32>,
@@ -160,7 +211,7 @@ mod tests {
     fn test_calculate_distance1() {
         let raw = r#"R8,U5,L5,D3
 U7,R6,D4,L4"#;
-        let distance = calculate_distance(raw);
+        let distance = calculate_distance(&parse(raw).unwrap());
         assert_eq!(distance, 6);
     }
 
@@ -168,7 +219,7 @@ U7,R6,D4,L4"#;
     fn test_calculate_distance2() {
         let raw = r#"R75,D30,R83,U83,L12,D49,R71,U7,L72
 U62,R66,U55,R34,D71,R55,D58,R83"#;
-        let distance = calculate_distance(raw);
+        let distance = calculate_distance(&parse(raw).unwrap());
         assert_eq!(distance, 159);
     }
 
@@ -176,7 +227,7 @@ U62,R66,U55,R34,D71,R55,D58,R83"#;
     fn test_calculate_distance3() {
         let raw = r#"R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
 U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"#;
-        let distance = calculate_distance(raw);
+        let distance = calculate_distance(&parse(raw).unwrap());
         assert_eq!(distance, 135);
     }
 
@@ -184,7 +235,7 @@ U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"#;
     fn test_calculate_signal_delay1() {
         let raw = r#"R8,U5,L5,D3
 U7,R6,D4,L4"#;
-        let distance = calculate_signal_delay(raw);
+        let distance = calculate_signal_delay(&parse(raw).unwrap());
         assert_eq!(distance, 30);
     }
 
@@ -192,7 +243,7 @@ U7,R6,D4,L4"#;
     fn test_calculate_signal_delay2() {
         let raw = r#"R75,D30,R83,U83,L12,D49,R71,U7,L72
 U62,R66,U55,R34,D71,R55,D58,R83"#;
-        let distance = calculate_signal_delay(raw);
+        let distance = calculate_signal_delay(&parse(raw).unwrap());
         assert_eq!(distance, 610);
     }
 
@@ -200,7 +251,27 @@ U62,R66,U55,R34,D71,R55,D58,R83"#;
     fn test_calculate_signal_delay3() {
         let raw = r#"R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
 U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"#;
-        let distance = calculate_signal_delay(raw);
+        let distance = calculate_signal_delay(&parse(raw).unwrap());
         assert_eq!(distance, 410);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("R8,U5,X5\nU7").unwrap_err(),
+            ParseError::new(1, 7, "expected U, D, L or R, found \"X\"")
+        );
+        assert_eq!(
+            parse("R8\nU7,D").unwrap_err(),
+            ParseError::new(2, 5, "expected a number, found \"\"")
+        );
+        assert_eq!(
+            parse("R8,U5").unwrap_err(),
+            ParseError::new(1, 6, "expected a second wire")
+        );
+        assert_eq!(
+            parse("R1\nU1").unwrap_err(),
+            ParseError::new(2, 3, "the wires never cross")
+        );
+    }
 }
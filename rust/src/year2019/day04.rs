@@ -1,28 +1,45 @@
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
-
-pub fn run_part1() {
-    let mut count = 0;
-    for i in 138241..=674034 {
-        if is_valid_password(i) {
-            count += 1;
-        }
+use std::ops::RangeInclusive;
+
+/// Secure Container: how many numbers in a range could be the password.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2019::day04::Day04;
+///
+/// let range = Day04::parse("111110-111125")?;
+/// assert_eq!(Day04::part1(&range), 13);
+/// assert_eq!(Day04::part2(&range), 1);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input<'a> = RangeInclusive<i32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (start, end) = error::split_once(data, data.trim(), '-')?;
+        Ok(error::number(data, start)?..=error::number(data, end)?)
     }
 
-    println!("{} valid passwords", count);
-}
-
-pub fn run_part2() {
-    let mut count = 0;
-    for i in 138241..=674034 {
-        if is_valid_strict_password(i) {
-            count += 1;
-        }
+    fn part1(range: &Self::Input<'_>) -> usize {
+        range.clone().filter(|&i| is_valid_password(i)).count()
     }
 
-    println!("{} valid passwords", count);
+    fn part2(range: &Self::Input<'_>) -> usize {
+        range
+            .clone()
+            .filter(|&i| is_valid_strict_password(i))
+            .count()
+    }
 }
 
-fn is_valid_password(password: i32) -> bool {
+/// Whether the digits of `password` never decrease and two next to each other are the same.
+pub fn is_valid_password(password: i32) -> bool {
     let digits = get_digits(password);
     let mut adjacent_digits_match = false;
     let mut previous_digit = 0;
@@ -42,7 +59,15 @@ fn is_valid_password(password: i32) -> bool {
     adjacent_digits_match
 }
 
-fn is_valid_strict_password(password: i32) -> bool {
+/// Whether the digits of `password` never decrease and some digit appears exactly twice in a row.
+///
+/// ```
+/// use aoc::year2019::day04::is_valid_strict_password;
+///
+/// assert!(is_valid_strict_password(111122));
+/// assert!(!is_valid_strict_password(123444));
+/// ```
+pub fn is_valid_strict_password(password: i32) -> bool {
     let digits = get_digits(password);
     let mut previous_digit = 0;
     let mut map = HashMap::new();
@@ -71,7 +96,7 @@ fn get_digits(password: i32) -> Vec<i32> {
 
     loop {
         components.push(remaining % 10);
-        remaining = remaining / 10;
+        remaining /= 10;
 
         if remaining <= 0 {
             break;
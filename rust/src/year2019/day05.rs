@@ -1,15 +1,94 @@
-use crate::year2019::vm::Intcode;
+use crate::anonymise::{self, Rng};
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::year2019::vm::{parse_program, Channels, Intcode, Status};
 
-const DATA: &str = include_str!("../../data/year2019/day05.txt");
+/// Sunny with a Chance of Asteroids: the diagnostic code the program gives for the air
+/// conditioner (system 1) and the thermal radiator controller (system 5).
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2019::day05::Day05;
+///
+/// // echoes the system ID
+/// let program = Day05::parse("3,0,4,0,99")?;
+/// assert_eq!(Day05::part1(&program), Ok(1));
+/// assert_eq!(Day05::part2(&program), Ok(5));
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day05;
 
-pub fn run() {
-    let mut memory = read_file();
+impl Solution for Day05 {
+    type Input<'a> = Vec<i64>;
+    type Answer1 = Result<i64, ParseError>;
+    type Answer2 = Result<i64, ParseError>;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_program(data)
+    }
+
+    fn part1(program: &Self::Input<'_>) -> Result<i64, ParseError> {
+        run_diagnostics(program, 1)
+    }
+
+    fn part2(program: &Self::Input<'_>) -> Result<i64, ParseError> {
+        run_diagnostics(program, 5)
+    }
+
+    fn synthesise(_: &str, _: &mut Rng) -> Result<String, &'static str> {
+        Err(anonymise::OPAQUE)
+    }
+}
+
+/// Runs the program interactively, asking for the system ID on stdin.
+pub fn run(data: &str) -> Result<(), ParseError> {
+    let mut memory = parse_program(data)?;
     let mut intcode = Intcode::new(&mut memory);
     intcode.run();
+    Ok(())
 }
 
-fn read_file() -> Vec<i32> {
-    DATA.split(",")
-        .flat_map(|x| x.trim().parse::<i32>())
-        .collect()
+/// Runs the diagnostic program for `system_id`. Every output but the last is a test result that
+/// should be 0; the last is the diagnostic code. Fails if the program doesn't halt with one, or
+/// a test fails.
+pub fn run_diagnostics(program: &[i64], system_id: i64) -> Result<i64, ParseError> {
+    let mut memory = program.to_vec();
+    let mut intcode = Intcode::new(&mut memory);
+    let mut channels = Channels::new(&[system_id]);
+    let status = intcode.run_with(&mut channels);
+    if status != Status::Halted {
+        return Err(ParseError::whole(status.to_string()));
+    }
+
+    let Some((code, tests)) = channels.output.split_last() else {
+        return Err(ParseError::whole("the program gave no diagnostic code"));
+    };
+    if tests.iter().any(|x| *x != 0) {
+        return Err(ParseError::whole(format!("failed tests: {:?}", tests)));
+    }
+    Ok(*code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_diagnostics() {
+        let diagnose = |program: &[i64]| run_diagnostics(program, 1).map_err(|e| e.to_string());
+        assert_eq!(diagnose(&[3, 0, 4, 0, 99]), Ok(1));
+        assert_eq!(diagnose(&[4, 2, 99]), Ok(99));
+        assert_eq!(
+            diagnose(&[3, 0, 99]).unwrap_err(),
+            "the program gave no diagnostic code"
+        );
+        assert_eq!(
+            diagnose(&[3, 0, 3, 0, 99]).unwrap_err(),
+            "the program is waiting for input"
+        );
+        assert_eq!(
+            diagnose(&[104, 1, 104, 0, 104, 7, 99]).unwrap_err(),
+            "failed tests: [1, 0]"
+        );
+    }
 }
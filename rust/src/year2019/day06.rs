@@ -1,19 +1,87 @@
+use crate::anonymise::Rng;
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 use std::collections::{HashMap, HashSet};
 
-const DATA: &str = include_str!("../../data/year2019/day06.txt");
+/// Universal Orbit Map: how many direct and indirect orbits there are, and how many orbital
+/// transfers it takes to get from the object YOU orbit to the one SAN orbits.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2019::day06::Day06;
+///
+/// let map = Day06::parse("COM)B\nB)C\nC)D\nD)YOU\nB)SAN")?;
+/// assert_eq!(Day06::part1(&map), 1 + 2 + 3 + 4 + 2);
+/// assert_eq!(Day06::part2(&map), Ok(2));
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input<'a> = Tree;
+    type Answer1 = i32;
+    type Answer2 = Result<usize, ParseError>;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        Tree::parse(data)
+    }
 
-pub fn run_part1() {
-    let tree = Tree::parse(DATA);
+    fn part1(tree: &Self::Input<'_>) -> i32 {
+        tree.total_orbits()
+    }
 
-    let total_orbits = tree.total_orbits();
-    println!("Total orbits: {}", total_orbits);
-}
+    fn part2(tree: &Self::Input<'_>) -> Result<usize, ParseError> {
+        tree.orbital_transfers("YOU", "SAN")
+            .ok_or_else(|| ParseError::whole("expected YOU and SAN on the map"))
+    }
 
-pub fn run_part2() {
-    let tree = Tree::parse(DATA);
+    /// A random map with as many orbits, keeping YOU and SAN at the ends of branches. Names
+    /// are three symbols long, or longer when a map is too big for there to be enough of them.
+    fn synthesise(data: &str, rng: &mut Rng) -> Result<String, &'static str> {
+        let lines = data.lines().collect::<Vec<_>>();
+        let leaves = ["YOU", "SAN"]
+            .into_iter()
+            .filter(|leaf| {
+                lines
+                    .iter()
+                    .any(|line| line.ends_with(&format!("){}", leaf)))
+            })
+            .collect::<Vec<_>>();
+
+        let symbols = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        // at least twice as many names as are needed, so a free one is quick to find
+        let length = (3..)
+            .find(|&n| symbols.len().saturating_pow(n) >= 2 * (lines.len() + 3))
+            .unwrap();
+        let mut objects = vec!["COM".to_owned()];
+        let mut taken = ["COM", "YOU", "SAN"]
+            .map(str::to_owned)
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut orbits = Vec::new();
+        for _ in leaves.len()..lines.len() {
+            let object = loop {
+                let name = (0..length)
+                    .map(|_| *rng.choose(symbols) as char)
+                    .collect::<String>();
+                if taken.insert(name.clone()) {
+                    break name;
+                }
+            };
+            orbits.push(format!("{}){}", rng.choose(&objects), object));
+            objects.push(object);
+        }
+        for leaf in leaves {
+            orbits.push(format!("{}){}", rng.choose(&objects), leaf));
+        }
+        rng.shuffle(&mut orbits);
 
-    let transfers = tree.orbital_transfers("YOU", "SAN");
-    println!("Orbital transfers: {}", transfers);
+        let mut map = orbits.join("\n");
+        if data.ends_with('\n') {
+            map.push('\n');
+        }
+        Ok(map)
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -33,21 +101,29 @@ impl Node {
     }
 }
 
+/// Which object orbits which, from the universal center of mass, COM.
 #[derive(Debug, Eq, PartialEq)]
-struct Tree {
+pub struct Tree {
     nodes: HashMap<String, Node>,
 }
 
 impl Tree {
-    fn parse(data: &str) -> Self {
+    /// Reads a map of `A)B` lines, each saying that B orbits A. COM has to be on it, orbiting
+    /// nothing, and every other object has to orbit exactly one object and lead back to COM.
+    pub fn parse(data: &str) -> Result<Self, ParseError> {
         let mut tree = Self {
             nodes: HashMap::new(),
         };
 
         for line in data.lines().map(|x| x.trim()) {
-            let mut iterator = line.split(")");
-            let planet_id = iterator.next().unwrap();
-            let satellite = iterator.next().unwrap();
+            let (planet_id, satellite) = error::split_once(data, line, ')')?;
+            if satellite == "COM" {
+                return Err(ParseError::at(data, satellite, "COM can't orbit anything"));
+            }
+            if let Some(parent) = tree.nodes.get(satellite).and_then(|n| n.parent.as_ref()) {
+                let message = format!("{} already orbits {}", satellite, parent);
+                return Err(ParseError::at(data, satellite, message));
+            }
 
             tree.ensure_planet_exists(planet_id);
             tree.ensure_planet_exists(satellite);
@@ -59,7 +135,29 @@ impl Tree {
             satellite.parent = Some(planet_id.to_owned());
         }
 
-        tree
+        if !tree.nodes.contains_key("COM") {
+            let last = data.lines().last().unwrap_or(data);
+            return Err(ParseError::after(data, last, "expected COM"));
+        }
+
+        // every object orbits one other, so a loop can only be among objects COM doesn't reach
+        let mut reached = HashSet::from(["COM"]);
+        let mut queue = vec!["COM"];
+        while let Some(id) = queue.pop() {
+            for child in &tree.nodes[id].children {
+                if reached.insert(child.as_str()) {
+                    queue.push(child);
+                }
+            }
+        }
+        for line in data.lines().map(|x| x.trim()) {
+            let (_, satellite) = line.split_once(')').unwrap();
+            if !reached.contains(satellite) {
+                let message = format!("{} doesn't lead back to COM", satellite);
+                return Err(ParseError::at(data, satellite, message));
+            }
+        }
+        Ok(tree)
     }
 
     fn ensure_planet_exists(&mut self, id: &str) {
@@ -69,7 +167,8 @@ impl Tree {
         }
     }
 
-    fn total_orbits(&self) -> i32 {
+    /// Counts every object each object orbits, directly or not.
+    pub fn total_orbits(&self) -> i32 {
         let mut total = 0;
         let mut nodes = vec![(self.nodes.get("COM").unwrap(), 0)];
 
@@ -86,24 +185,37 @@ impl Tree {
         total
     }
 
-    fn path(&self, id: &str) -> Vec<String> {
+    fn path(&self, id: &str) -> Option<Vec<String>> {
         let mut path = Vec::new();
 
-        let mut node = self.nodes.get(id).unwrap();
+        let mut node = self.nodes.get(id)?;
         while let Some(parent) = &node.parent {
             path.push(parent.to_owned());
             node = self.nodes.get(parent).unwrap();
         }
 
-        path
+        Some(path)
     }
 
-    fn orbital_transfers(&self, source: &str, destination: &str) -> usize {
-        let you: HashSet<String> = self.path(source).into_iter().collect();
-        let san: HashSet<String> = self.path(destination).into_iter().collect();
-        you.symmetric_difference(&san)
+    /// How many moves it takes to go from the object `source` orbits to the one `destination` orbits,
+    /// or None if either isn't on the map.
+    ///
+    /// ```
+    /// use aoc::year2019::day06::Tree;
+    ///
+    /// let tree = Tree::parse("COM)B\nB)C\nC)YOU\nB)SAN")?;
+    /// assert_eq!(tree.orbital_transfers("YOU", "SAN"), Some(1));
+    /// assert_eq!(tree.orbital_transfers("YOU", "X"), None);
+    /// # Ok::<(), aoc::error::ParseError>(())
+    /// ```
+    pub fn orbital_transfers(&self, source: &str, destination: &str) -> Option<usize> {
+        let you: HashSet<String> = self.path(source)?.into_iter().collect();
+        let san: HashSet<String> = self.path(destination)?.into_iter().collect();
+        let transfers = you
+            .symmetric_difference(&san)
             .collect::<HashSet<&String>>()
-            .len()
+            .len();
+        Some(transfers)
     }
 }
 
@@ -139,7 +251,7 @@ I)SAN"#;
 
     #[test]
     fn test_parse_single_satellite() {
-        let tree = Tree::parse("COM)B");
+        let tree = Tree::parse("COM)B").unwrap();
         println!("{:#?}", tree);
 
         let mut expected: HashMap<String, Node> = HashMap::new();
@@ -167,22 +279,79 @@ I)SAN"#;
 
     #[test]
     fn test_parse_multiple_satellites() {
-        let tree = Tree::parse(DATA);
+        let tree = Tree::parse(DATA).unwrap();
         println!("{:#?}", tree);
     }
 
     #[test]
     fn test_total_orbits() {
-        let tree = Tree::parse(DATA);
+        let tree = Tree::parse(DATA).unwrap();
 
         assert_eq!(tree.total_orbits(), 42);
     }
 
     #[test]
     fn test_orbital_transfers() {
-        let tree = Tree::parse(DATA2);
+        let tree = Tree::parse(DATA2).unwrap();
         let transfers = tree.orbital_transfers("YOU", "SAN");
 
-        assert_eq!(transfers, 4);
+        assert_eq!(transfers, Some(4));
+    }
+
+    #[test]
+    fn test_no_you_or_san() {
+        let tree = Tree::parse(DATA).unwrap();
+        assert_eq!(
+            Day06::part2(&tree),
+            Err(ParseError::whole("expected YOU and SAN on the map"))
+        );
+    }
+
+    #[test]
+    fn test_synthesise_large_map() {
+        let data = (0..50_000)
+            .map(|i| format!("{}){}", if i == 0 { "COM" } else { "A" }, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let made = Day06::synthesise(&data, &mut Rng::new(1)).unwrap();
+        let satellites = made
+            .lines()
+            .map(|line| line.split_once(')').unwrap().1)
+            .collect::<HashSet<_>>();
+        assert_eq!(satellites.len(), 50_000);
+        assert!(satellites.iter().all(|name| name.len() == 4));
+        assert!(Day06::parse(&made).is_ok());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            Tree::parse("COM)B\nB-C"),
+            Err(ParseError::new(2, 4, "expected ')'"))
+        );
+        assert_eq!(
+            Tree::parse("A)B"),
+            Err(ParseError::new(1, 4, "expected COM"))
+        );
+        assert_eq!(
+            Tree::parse("COM)B\nB)COM"),
+            Err(ParseError::new(2, 3, "COM can't orbit anything"))
+        );
+        assert_eq!(
+            Tree::parse("COM)B\nB)C\nC)B"),
+            Err(ParseError::new(3, 3, "B already orbits COM"))
+        );
+        assert_eq!(
+            Tree::parse("COM)B\nB)X\nA)X"),
+            Err(ParseError::new(3, 3, "X already orbits B"))
+        );
+        assert_eq!(
+            Tree::parse("COM)B\nC)D\nD)C"),
+            Err(ParseError::new(2, 3, "D doesn't lead back to COM"))
+        );
+        assert_eq!(
+            Tree::parse("COM)B\nA)C"),
+            Err(ParseError::new(2, 3, "C doesn't lead back to COM"))
+        );
     }
 }
@@ -0,0 +1,564 @@
+use crate::anonymise::{self, Rng};
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::year2019::ascii::{Ascii, Reply, State};
+use crate::year2019::vm::{parse_program, Snapshot};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Items that end the game or trap the droid as soon as they are picked up.
+const TRAPS: [&str; 5] = [
+    "escape pod",
+    "giant electromagnet",
+    "infinite loop",
+    "molten lava",
+    "photons",
+];
+
+/// What part 2 answers: there's no puzzle, the last star comes from finishing every other day.
+pub const NO_PART2: &str = "no part 2";
+
+/// Cryostasis: the password for the airlock, found by exploring the ship and carrying the right
+/// items through the pressure-sensitive floor. It needs the real program, so there's no example.
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input<'a> = Vec<i64>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_program(data)
+    }
+
+    fn part1(program: &Self::Input<'_>) -> String {
+        let mut memory = program.clone();
+        let mut droid = Ascii::new(&mut memory);
+        solve(&mut droid).unwrap_or_else(|e| panic!("droid failed: {}", e))
+    }
+
+    /// Day 25 only has one puzzle, so this is always [`NO_PART2`]. The registry leaves the part
+    /// out, so it's never run or submitted.
+    fn part2(_: &Self::Input<'_>) -> String {
+        NO_PART2.to_owned()
+    }
+
+    fn synthesise(_: &str, _: &mut Rng) -> Result<String, &'static str> {
+        Err(anonymise::OPAQUE)
+    }
+}
+
+/// A text interface to the game that can be rewound, so every door can be tried without
+/// walking back.
+trait Terminal {
+    type Snapshot;
+
+    fn read(&mut self) -> Reply;
+    fn send(&mut self, command: &str) -> Reply;
+    fn snapshot(&self) -> Self::Snapshot;
+    fn restore(&mut self, snapshot: &Self::Snapshot);
+}
+
+impl Terminal for Ascii<'_> {
+    type Snapshot = Snapshot;
+
+    fn read(&mut self) -> Reply {
+        Ascii::read(self)
+    }
+
+    fn send(&mut self, command: &str) -> Reply {
+        Ascii::send(self, command)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Ascii::snapshot(self)
+    }
+
+    fn restore(&mut self, snapshot: &Snapshot) {
+        Ascii::restore(self, snapshot)
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct Room {
+    name: String,
+    description: String,
+    doors: Vec<String>,
+    items: Vec<String>,
+}
+
+/// Parses every room the text describes, in the order they appear. Being ejected from the
+/// pressure-sensitive floor prints two rooms, the last of which is where the droid ends up.
+fn parse_rooms(text: &str) -> Vec<Room> {
+    let mut rooms: Vec<Room> = Vec::new();
+    let mut section = Section::Description;
+
+    for line in text.lines().map(|l| l.trim()) {
+        if let Some(name) = line.strip_prefix("== ").and_then(|l| l.strip_suffix(" ==")) {
+            rooms.push(Room {
+                name: name.to_owned(),
+                ..Room::default()
+            });
+            section = Section::Description;
+            continue;
+        }
+
+        let Some(room) = rooms.last_mut() else {
+            continue;
+        };
+
+        match (line, section) {
+            ("", Section::Description) => {}
+            ("", _) => section = Section::Other,
+            ("Doors here lead:", _) => section = Section::Doors,
+            ("Items here:", _) => section = Section::Items,
+            (line, Section::Description) => {
+                if !room.description.is_empty() {
+                    room.description.push('\n');
+                }
+                room.description.push_str(line);
+            }
+            (line, Section::Doors) => room.doors.extend(line.strip_prefix("- ").map(String::from)),
+            (line, Section::Items) => room.items.extend(line.strip_prefix("- ").map(String::from)),
+            (_, Section::Other) => {}
+        }
+    }
+
+    rooms
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Description,
+    Doors,
+    Items,
+    Other,
+}
+
+/// Everything learned about the ship by trying every door.
+#[derive(Debug, Default)]
+struct Ship {
+    start: String,
+    /// Rooms in the order they were discovered.
+    rooms: Vec<Room>,
+    doors: HashMap<(String, String), String>,
+    /// The room before the pressure-sensitive floor and the door leading onto it.
+    checkpoint: Option<(String, String)>,
+}
+
+impl Ship {
+    fn room(&self, name: &str) -> Option<&Room> {
+        self.rooms.iter().find(|r| r.name == name)
+    }
+
+    /// The doors to go through to get from `from` to `to`.
+    fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: HashMap<&str, (&str, &str)> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(name) = queue.pop_front() {
+            if name == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while let Some((room, door)) = previous.get(current) {
+                    path.push(door.to_string());
+                    current = room;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for door in &self.room(name)?.doors {
+                if let Some(next) = self.doors.get(&(name.to_owned(), door.to_owned())) {
+                    if next != from && !previous.contains_key(next.as_str()) {
+                        previous.insert(next, (name, door));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Maps the ship breadth-first, rewinding to each room's snapshot before trying its doors.
+/// Leaves the terminal where it started.
+fn explore<T: Terminal>(terminal: &mut T) -> Result<Ship, String> {
+    let start = terminal.snapshot();
+    let reply = terminal.read();
+    let room = parse_rooms(&reply.text)
+        .pop()
+        .ok_or_else(|| format!("no room in the opening text: {:?}", reply.text))?;
+
+    let mut ship = Ship {
+        start: room.name.clone(),
+        ..Ship::default()
+    };
+    let mut queue = VecDeque::from([(room.name.clone(), terminal.snapshot())]);
+    ship.rooms.push(room);
+
+    while let Some((name, snapshot)) = queue.pop_front() {
+        let doors = ship
+            .room(&name)
+            .map(|r| r.doors.clone())
+            .unwrap_or_default();
+        for door in doors {
+            terminal.restore(&snapshot);
+            let reply = terminal.send(&door);
+            if reply.state != State::Waiting {
+                continue;
+            }
+            let Some(room) = parse_rooms(&reply.text).pop() else {
+                continue;
+            };
+
+            if room.name == name && reply.text.contains("Alert!") {
+                ship.checkpoint = Some((name.clone(), door));
+                continue;
+            }
+
+            ship.doors.insert((name.clone(), door), room.name.clone());
+            if ship.room(&room.name).is_none() {
+                queue.push_back((room.name.clone(), terminal.snapshot()));
+                ship.rooms.push(room);
+            }
+        }
+    }
+
+    terminal.restore(&start);
+    terminal.read();
+    Ok(ship)
+}
+
+fn walk<T: Terminal>(terminal: &mut T, ship: &Ship, from: &str, to: &str) -> Result<(), String> {
+    let path = ship
+        .path(from, to)
+        .ok_or_else(|| format!("no way from {} to {}", from, to))?;
+    for door in path {
+        let reply = terminal.send(&door);
+        if reply.state != State::Waiting {
+            return Err(format!("the droid stopped responding going {}", door));
+        }
+    }
+    Ok(())
+}
+
+/// Picks up `item`, rewinding if doing so ends the game.
+fn take<T: Terminal>(terminal: &mut T, item: &str) -> bool {
+    let snapshot = terminal.snapshot();
+    let reply = terminal.send(&format!("take {}", item));
+    if reply.state == State::Waiting && reply.text.contains("You take") {
+        true
+    } else {
+        terminal.restore(&snapshot);
+        false
+    }
+}
+
+/// Drops and picks up one item at a time, in Gray-code order, until the floor lets the droid
+/// through. Expects to be holding every item in `items`.
+fn pass_checkpoint<T: Terminal>(
+    terminal: &mut T,
+    items: &[String],
+    door: &str,
+) -> Result<String, String> {
+    let mut held = vec![true; items.len()];
+
+    for i in 0..1u64 << items.len() {
+        if i > 0 {
+            let changed = i.trailing_zeros() as usize;
+            let verb = if held[changed] { "drop" } else { "take" };
+            terminal.send(&format!("{} {}", verb, items[changed]));
+            held[changed] = !held[changed];
+        }
+
+        let reply = terminal.send(door);
+        if !reply.text.contains("Alert!") {
+            return Ok(password(&reply.text).unwrap_or(reply.text));
+        }
+    }
+
+    Err("no combination of items gets past the checkpoint".to_owned())
+}
+
+/// The longest run of digits in the text, which is the airlock password.
+fn password(text: &str) -> Option<String> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .max_by_key(|digits| digits.len())
+        .filter(|digits| !digits.is_empty())
+        .map(|digits| digits.to_owned())
+}
+
+/// Maps the ship, collects every item that isn't a trap and finds the weight that gets the
+/// droid past the security checkpoint.
+fn solve<T: Terminal>(terminal: &mut T) -> Result<String, String> {
+    let ship = explore(terminal)?;
+    let (checkpoint, door) = ship
+        .checkpoint
+        .clone()
+        .ok_or("the pressure-sensitive floor was never found")?;
+
+    let traps = TRAPS.iter().copied().collect::<HashSet<_>>();
+    let mut location = ship.start.clone();
+    let mut inventory = Vec::new();
+
+    for room in &ship.rooms {
+        for item in &room.items {
+            if traps.contains(item.as_str()) {
+                continue;
+            }
+
+            walk(terminal, &ship, &location, &room.name)?;
+            location = room.name.clone();
+            if take(terminal, item) {
+                inventory.push(item.clone());
+            }
+        }
+    }
+
+    walk(terminal, &ship, &location, &checkpoint)?;
+    pass_checkpoint(terminal, &inventory, &door)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HULL_BREACH: &str = "
+
+
+== Hull Breach ==
+You got in through a hole in the floor here. To keep your ship from also freezing, the hole has been sealed.
+
+Doors here lead:
+- north
+- south
+
+Items here:
+- mug
+- hologram
+
+Command?
+";
+
+    #[derive(Clone)]
+    struct FakeRoom {
+        name: &'static str,
+        doors: Vec<(&'static str, &'static str)>,
+        items: Vec<&'static str>,
+    }
+
+    /// A small ship laid out like the real one: an escape pod, a checkpoint in a dead end and a
+    /// floor that wants exactly the mug and the coin.
+    #[derive(Clone)]
+    struct FakeShip {
+        rooms: Vec<FakeRoom>,
+        location: &'static str,
+        inventory: Vec<&'static str>,
+        halted: bool,
+    }
+
+    impl FakeShip {
+        fn new() -> Self {
+            let room = |name, doors, items| FakeRoom { name, doors, items };
+            Self {
+                rooms: vec![
+                    room(
+                        "Hull Breach",
+                        vec![("north", "Kitchen"), ("east", "Hallway")],
+                        vec![],
+                    ),
+                    room(
+                        "Kitchen",
+                        vec![("south", "Hull Breach")],
+                        vec!["mug", "escape pod"],
+                    ),
+                    room(
+                        "Hallway",
+                        vec![("west", "Hull Breach"), ("east", "Security Checkpoint")],
+                        vec!["coin", "boulder"],
+                    ),
+                    room(
+                        "Security Checkpoint",
+                        vec![("west", "Hallway"), ("north", "Pressure-Sensitive Floor")],
+                        vec!["feather"],
+                    ),
+                ],
+                location: "Hull Breach",
+                inventory: Vec::new(),
+                halted: false,
+            }
+        }
+
+        fn room(&mut self, name: &str) -> &mut FakeRoom {
+            self.rooms.iter_mut().find(|r| r.name == name).unwrap()
+        }
+
+        fn describe(&mut self, name: &str) -> String {
+            let room = self.room(name);
+            let mut text = format!("\n\n\n== {} ==\nA room.\n\nDoors here lead:\n", room.name);
+            for (door, _) in &room.doors {
+                text.push_str(&format!("- {}\n", door));
+            }
+            if !room.items.is_empty() {
+                text.push_str("\nItems here:\n");
+                for item in &room.items {
+                    text.push_str(&format!("- {}\n", item));
+                }
+            }
+            text
+        }
+
+        fn reply(&self, text: String) -> Reply {
+            Reply {
+                text: if self.halted {
+                    text
+                } else {
+                    text + "\nCommand?\n"
+                },
+                values: Vec::new(),
+                state: if self.halted {
+                    State::Halted
+                } else {
+                    State::Waiting
+                },
+            }
+        }
+    }
+
+    impl Terminal for FakeShip {
+        type Snapshot = FakeShip;
+
+        fn read(&mut self) -> Reply {
+            let text = self.describe(self.location);
+            self.reply(text)
+        }
+
+        fn send(&mut self, command: &str) -> Reply {
+            let location = self.location;
+            let text = if let Some(item) = command.strip_prefix("take ") {
+                let room = self.room(location);
+                let index = room.items.iter().position(|i| *i == item).unwrap();
+                let item = room.items.remove(index);
+                if item == "escape pod" {
+                    self.halted = true;
+                    "\nYou're launched into space! Bye!\n".to_owned()
+                } else {
+                    self.inventory.push(item);
+                    format!("\nYou take the {}.\n", item)
+                }
+            } else if let Some(item) = command.strip_prefix("drop ") {
+                let index = self.inventory.iter().position(|i| *i == item).unwrap();
+                let item = self.inventory.remove(index);
+                self.room(location).items.push(item);
+                format!("\nYou drop the {}.\n", item)
+            } else {
+                let room = self.room(location);
+                let (_, next) = *room.doors.iter().find(|(d, _)| *d == command).unwrap();
+                if next != "Pressure-Sensitive Floor" {
+                    self.location = next;
+                    self.describe(next)
+                } else {
+                    let mut held = self.inventory.clone();
+                    held.sort();
+                    let floor = "\n\n\n== Pressure-Sensitive Floor ==\nAnalyzing...\n\nDoors here lead:\n- south\n";
+                    if held == ["coin", "mug"] {
+                        self.halted = true;
+                        format!(
+                            "{}\nSanta says you can get in by typing 8675309 on the keypad.\n",
+                            floor
+                        )
+                    } else {
+                        format!(
+                            "{}\nA loud, robotic voice says \"Alert! Droids on this ship are heavier than the detected value!\" and you are ejected back to the checkpoint.\n{}",
+                            floor,
+                            self.describe(location)
+                        )
+                    }
+                }
+            };
+            self.reply(text)
+        }
+
+        fn snapshot(&self) -> FakeShip {
+            self.clone()
+        }
+
+        fn restore(&mut self, snapshot: &FakeShip) {
+            *self = snapshot.clone();
+        }
+    }
+
+    #[test]
+    fn test_parse_rooms() {
+        let rooms = parse_rooms(HULL_BREACH);
+        let expected = Room {
+            name: "Hull Breach".to_owned(),
+            description: "You got in through a hole in the floor here. To keep your ship from also freezing, the hole has been sealed.".to_owned(),
+            doors: vec!["north".to_owned(), "south".to_owned()],
+            items: vec!["mug".to_owned(), "hologram".to_owned()],
+        };
+        assert_eq!(rooms, vec![expected]);
+    }
+
+    #[test]
+    fn test_parse_rooms_ejected() {
+        let mut ship = FakeShip::new();
+        ship.location = "Security Checkpoint";
+        let reply = ship.send("north");
+        let names = parse_rooms(&reply.text)
+            .into_iter()
+            .map(|r| r.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Pressure-Sensitive Floor", "Security Checkpoint"]);
+    }
+
+    #[test]
+    fn test_explore() {
+        let mut ship = FakeShip::new();
+        let map = explore(&mut ship).unwrap();
+        let names = map
+            .rooms
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["Hull Breach", "Kitchen", "Hallway", "Security Checkpoint"]
+        );
+        assert_eq!(
+            map.checkpoint,
+            Some(("Security Checkpoint".to_owned(), "north".to_owned()))
+        );
+        assert_eq!(
+            map.path("Kitchen", "Security Checkpoint"),
+            Some(vec![
+                "south".to_owned(),
+                "east".to_owned(),
+                "east".to_owned()
+            ])
+        );
+        assert_eq!(ship.location, "Hull Breach");
+    }
+
+    #[test]
+    fn test_password() {
+        assert_eq!(
+            password("typing 8675309 on the keypad"),
+            Some("8675309".to_owned())
+        );
+        assert_eq!(password("no digits"), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut ship = FakeShip::new();
+        assert_eq!(solve(&mut ship), Ok("8675309".to_owned()));
+        assert!(ship.halted);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(Day25::part2(&vec![99]), NO_PART2);
+    }
+}
@@ -1,7 +1,15 @@
+pub mod ascii;
+pub mod batch;
+pub mod compiler;
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
 pub mod day06;
+pub mod day25;
+#[cfg(test)]
+mod parity;
+pub mod replay;
 pub mod vm;
+pub mod wat;
@@ -0,0 +1,112 @@
+//! Checks the 2019 solutions against the answers the C# ones give, kept in
+//! `data/year2019/parity.txt`.
+
+use crate::input::DEFAULT_DATA_DIR;
+use crate::registry;
+use crate::year2019::vm::{parse_program, Intcode};
+use crate::year2019::{day01, day02};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixture_dir() -> PathBuf {
+    Path::new(DEFAULT_DATA_DIR).join("year2019")
+}
+
+/// One line of the fixture.
+#[derive(Debug)]
+struct Case<'a> {
+    line: usize,
+    day: u8,
+    function: &'a str,
+    arguments: Vec<String>,
+    expected: &'a str,
+}
+
+fn parse(fixture: &str) -> Result<Vec<Case<'_>>, String> {
+    let dir = fixture_dir();
+    fixture
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let invalid = || format!("line {}: invalid case {:?}", i + 1, line);
+            let (call, expected) = line.split_once(" = ").ok_or_else(invalid)?;
+            let mut words = call.split(' ');
+            let day = words
+                .next()
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(invalid)?;
+            let function = words.next().ok_or_else(invalid)?;
+            let arguments = words
+                .map(|word| match word.strip_prefix('@') {
+                    Some(file) => fs::read_to_string(dir.join(file))
+                        .map_err(|e| format!("line {}: {}: {}", i + 1, file, e)),
+                    None => Ok(word.to_owned()),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Case {
+                line: i + 1,
+                day,
+                function,
+                arguments,
+                expected: expected.trim(),
+            })
+        })
+        .collect()
+}
+
+/// What the Rust solution gives for `case`, shown the way the fixture shows it.
+fn evaluate(case: &Case) -> String {
+    let number = |i: usize| case.arguments[i].parse::<i64>().unwrap();
+    match (case.day, case.function) {
+        (1, "fuel") => day01::get_fuel_for_mass(number(0) as i32).to_string(),
+        (1, "fuel_for_fuel") => day01::get_fuel_for_module(number(0) as i32).to_string(),
+        (2, "run") => {
+            let mut memory = parse_program(&case.arguments[0]).unwrap();
+            Intcode::new(&mut memory).run();
+            let memory = memory.iter().map(i64::to_string).collect::<Vec<_>>();
+            memory.join(",")
+        }
+        (2, "patched") => {
+            let program = parse_program(&case.arguments[2]).unwrap();
+            day02::run(&program, number(0), number(1)).to_string()
+        }
+        (day, part @ ("part1" | "part2")) => {
+            let solve = registry::find_part(2019, day, part[4..].parse().unwrap()).unwrap();
+            solve(&case.arguments[0]).unwrap()
+        }
+        (day, function) => panic!("line {}: day {} has no {}", case.line, day, function),
+    }
+}
+
+#[test]
+fn test_parity() {
+    let fixture = fs::read_to_string(fixture_dir().join("parity.txt")).unwrap();
+    let cases = parse(&fixture).unwrap();
+    assert!(cases.len() > 30);
+
+    let mismatches = cases
+        .iter()
+        .filter_map(|case| {
+            let actual = evaluate(case);
+            (actual != case.expected).then(|| {
+                format!(
+                    "line {}: day {} {}: C# gives {}, Rust gives {}",
+                    case.line, case.day, case.function, case.expected, actual
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        parse("# comment\n\n1 fuel").unwrap_err(),
+        "line 3: invalid case \"1 fuel\""
+    );
+    assert!(parse("2 part1 @missing.txt = 1")
+        .unwrap_err()
+        .starts_with("line 1: missing.txt: "));
+}
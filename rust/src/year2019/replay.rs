@@ -0,0 +1,391 @@
+use crate::year2019::vm::{Intcode, Io, Status};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const HEADER: &str = "# intcode recording";
+
+/// One value going into or out of a machine, and how many instructions the machine had
+/// executed when it happened.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Event {
+    Input { step: u64, value: i64 },
+    Output { step: u64, value: i64 },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::Input { step, value } => write!(f, "{} in {}", step, value),
+            Event::Output { step, value } => write!(f, "{} out {}", step, value),
+        }
+    }
+}
+
+/// Every input and output of a session, in order. Saved as text, one event per line.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Recording {
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut events = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            let event = match parts[..] {
+                [step, kind, value] => {
+                    let step = step.parse();
+                    let value = value.parse();
+                    match (kind, step, value) {
+                        ("in", Ok(step), Ok(value)) => Some(Event::Input { step, value }),
+                        ("out", Ok(step), Ok(value)) => Some(Event::Output { step, value }),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match event {
+                Some(event) => events.push(event),
+                None => return Err(format!("line {}: invalid event {:?}", number + 1, line)),
+            }
+        }
+        Ok(Self { events })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let s = fs::read_to_string(path)?;
+        Self::parse(&s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl Display for Recording {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+/// Passes I/O through to another [`Io`] while noting down every value.
+struct Recorder<'a, I> {
+    inner: &'a mut I,
+    step: u64,
+    recording: &'a mut Recording,
+}
+
+impl<I: Io> Io for Recorder<'_, I> {
+    fn input(&mut self) -> Option<i64> {
+        let value = self.inner.input()?;
+        self.recording.events.push(Event::Input {
+            step: self.step,
+            value,
+        });
+        Some(value)
+    }
+
+    fn output(&mut self, value: i64) {
+        self.recording.events.push(Event::Output {
+            step: self.step,
+            value,
+        });
+        self.inner.output(value);
+    }
+}
+
+/// Runs `intcode` with `io` like [`Intcode::run_with`], appending every input and output to
+/// `recording`.
+pub fn record(intcode: &mut Intcode, io: &mut impl Io, recording: &mut Recording) -> Status {
+    let mut recorder = Recorder {
+        inner: io,
+        step: 0,
+        recording,
+    };
+    loop {
+        recorder.step = intcode.steps();
+        if let Some(status) = intcode.step(&mut recorder) {
+            return status;
+        }
+    }
+}
+
+/// Runs `intcode` with `io` and saves the session to `path`, even if the program panics part
+/// way through.
+pub fn record_to(intcode: &mut Intcode, io: &mut impl Io, path: &Path) -> io::Result<Status> {
+    let mut recording = Recording::default();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        record(intcode, io, &mut recording)
+    }));
+    recording.save(path)?;
+    result.map_err(|_| io::Error::other("the program panicked; the session was saved"))
+}
+
+/// Replays the session saved in `path` against a fresh copy of `program`.
+pub fn replay_file(program: &[i64], path: &Path) -> io::Result<Result<Status, Divergence>> {
+    let recording = Recording::load(path)?;
+    let mut memory = program.to_vec();
+    Ok(replay(&mut Intcode::new(&mut memory), &recording))
+}
+
+/// The first point at which a replayed session stopped matching its recording.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Divergence {
+    /// How many instructions the machine had executed when it diverged.
+    pub step: u64,
+    /// What the recording says happens next, or `None` if the recording had ended.
+    pub expected: Option<Event>,
+    /// What the machine did instead.
+    pub actual: Observed,
+}
+
+/// What a replayed machine did at the step it diverged.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Observed {
+    Input,
+    Output(i64),
+    /// An instruction that neither reads input nor writes output.
+    NoIo,
+    Halt,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let expected = match self.expected {
+            Some(Event::Input { step, .. }) => format!("input at step {}", step),
+            Some(Event::Output { step, value }) => format!("output {} at step {}", value, step),
+            None => "the end of the recording".to_owned(),
+        };
+        let actual = match self.actual {
+            Observed::Input => "input".to_owned(),
+            Observed::Output(value) => format!("output {}", value),
+            Observed::NoIo => "no input or output".to_owned(),
+            Observed::Halt => "a halt".to_owned(),
+        };
+        write!(
+            f,
+            "diverged at step {}: expected {}, got {}",
+            self.step, expected, actual
+        )
+    }
+}
+
+/// Feeds recorded inputs back to the machine and checks its outputs against the recording.
+struct Player<'a> {
+    events: &'a [Event],
+    position: usize,
+    step: u64,
+    divergence: Option<Divergence>,
+}
+
+impl Player<'_> {
+    fn diverge(&mut self, actual: Observed) {
+        if self.divergence.is_none() {
+            self.divergence = Some(Divergence {
+                step: self.step,
+                expected: self.events.get(self.position).copied(),
+                actual,
+            });
+        }
+    }
+}
+
+impl Io for Player<'_> {
+    fn input(&mut self) -> Option<i64> {
+        match self.events.get(self.position) {
+            Some(&Event::Input { step, value }) if step == self.step => {
+                self.position += 1;
+                Some(value)
+            }
+            // a session that ended while the program waited for input
+            None => None,
+            Some(_) => {
+                self.diverge(Observed::Input);
+                None
+            }
+        }
+    }
+
+    fn output(&mut self, value: i64) {
+        let actual = Event::Output {
+            step: self.step,
+            value,
+        };
+        if self.events.get(self.position) == Some(&actual) {
+            self.position += 1;
+        } else {
+            self.diverge(Observed::Output(value));
+        }
+    }
+}
+
+/// Replays `recording` against `intcode`, which should be in the state the recorded session
+/// started from.
+pub fn replay(intcode: &mut Intcode, recording: &Recording) -> Result<Status, Divergence> {
+    let mut player = Player {
+        events: &recording.events,
+        position: 0,
+        step: 0,
+        divergence: None,
+    };
+
+    loop {
+        player.step = intcode.steps();
+        let position = player.position;
+        let status = intcode.step(&mut player);
+
+        let expected_step = match player.events.get(position) {
+            Some(Event::Input { step, .. } | Event::Output { step, .. }) => Some(*step),
+            None => None,
+        };
+        match status {
+            Some(Status::Halted) if position < player.events.len() => {
+                player.diverge(Observed::Halt)
+            }
+            None if player.position == position && expected_step == Some(player.step) => {
+                player.diverge(Observed::NoIo)
+            }
+            _ => {}
+        }
+
+        if let Some(divergence) = player.divergence {
+            return Err(divergence);
+        }
+        if let Some(status) = status {
+            return Ok(status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::Scratch;
+    use crate::year2019::vm::Channels;
+
+    // Outputs 999, 1000 or 1001 depending on whether its input is below, equal to or above 8.
+    const COMPARE_WITH_8: [i64; 47] = [
+        3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0,
+        1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20,
+        1105, 1, 46, 98, 99,
+    ];
+
+    fn record_session(input: i64) -> Recording {
+        let mut memory = COMPARE_WITH_8;
+        let mut intcode = Intcode::new(&mut memory);
+        let mut recording = Recording::default();
+        let status = record(&mut intcode, &mut Channels::new(&[input]), &mut recording);
+        assert_eq!(status, Status::Halted);
+        recording
+    }
+
+    #[test]
+    fn test_record() {
+        let recording = record_session(8);
+        let expected = vec![
+            Event::Input { step: 0, value: 8 },
+            Event::Output {
+                step: 4,
+                value: 1000,
+            },
+        ];
+        assert_eq!(recording.events, expected);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let recording = record_session(3);
+        let text = recording.to_string();
+        assert_eq!(text, "# intcode recording\n0 in 3\n5 out 999\n");
+        assert_eq!(Recording::parse(&text), Ok(recording));
+        assert_eq!(
+            Recording::parse("0 in 3\n6 sideways 999"),
+            Err("line 2: invalid event \"6 sideways 999\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_record_to_file() {
+        let dir = Scratch::new("replay");
+        let path = dir.join("session.txt");
+        let mut memory = COMPARE_WITH_8;
+        let mut intcode = Intcode::new(&mut memory);
+        let mut io = Channels::new(&[8]);
+        assert_eq!(
+            record_to(&mut intcode, &mut io, &path).unwrap(),
+            Status::Halted
+        );
+        assert_eq!(io.output, [1000]);
+        assert_eq!(
+            replay_file(&COMPARE_WITH_8, &path).unwrap(),
+            Ok(Status::Halted)
+        );
+
+        let mut changed = COMPARE_WITH_8;
+        changed[4] = 7;
+        changed[10] = 7;
+        assert!(replay_file(&changed, &path).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let recording = record_session(9);
+        let mut memory = COMPARE_WITH_8;
+        let mut intcode = Intcode::new(&mut memory);
+        assert_eq!(replay(&mut intcode, &recording), Ok(Status::Halted));
+    }
+
+    #[test]
+    fn test_replay_different_output() {
+        let recording = record_session(8);
+        let mut memory = COMPARE_WITH_8;
+        // compare with 7 instead
+        memory[4] = 7;
+        memory[10] = 7;
+        let mut intcode = Intcode::new(&mut memory);
+        let divergence = replay(&mut intcode, &recording).unwrap_err();
+        assert_eq!(
+            divergence.to_string(),
+            "diverged at step 4: expected output 1000 at step 4, got no input or output"
+        );
+    }
+
+    #[test]
+    fn test_replay_halts_early() {
+        let mut recording = record_session(9);
+        recording.events.push(Event::Output { step: 9, value: 1 });
+        let mut memory = COMPARE_WITH_8;
+        let mut intcode = Intcode::new(&mut memory);
+        let divergence = replay(&mut intcode, &recording).unwrap_err();
+        assert_eq!(divergence.actual, Observed::Halt);
+        assert_eq!(
+            divergence.to_string(),
+            "diverged at step 9: expected output 1 at step 9, got a halt"
+        );
+    }
+
+    #[test]
+    fn test_replay_unexpected_input() {
+        let recording = Recording {
+            events: vec![Event::Output { step: 0, value: 1 }],
+        };
+        let mut memory = COMPARE_WITH_8;
+        let mut intcode = Intcode::new(&mut memory);
+        let divergence = replay(&mut intcode, &recording).unwrap_err();
+        assert_eq!(
+            divergence.to_string(),
+            "diverged at step 0: expected output 1 at step 0, got input"
+        );
+    }
+}
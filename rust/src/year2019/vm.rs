@@ -1,85 +1,374 @@
-use std::fmt::{Debug, Error, Formatter};
+use crate::error::{self, ParseError};
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display, Error, Formatter};
 use std::io;
+use std::sync::Arc;
+
+/// Reads an Intcode program: its memory as comma-separated numbers.
+pub fn parse_program(data: &str) -> Result<Vec<i64>, ParseError> {
+    data.split(',')
+        .map(|value| error::number(data, value.trim()))
+        .collect()
+}
 
 pub struct Intcode<'a> {
-    memory: &'a mut [i32],
+    memory: Memory<'a>,
     instruction_pointer: usize,
+    relative_base: i64,
+    steps: u64,
+}
+
+/// Why a call to [`Intcode::run_with`] returned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Status {
+    Halted,
+    /// The machine hit an input instruction while no input was available. Running it again
+    /// resumes at that instruction.
+    AwaitingInput,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Halted => write!(f, "the program halted"),
+            Status::AwaitingInput => write!(f, "the program is waiting for input"),
+        }
+    }
+}
+
+/// Where a machine reads its input from and writes its output to.
+pub trait Io {
+    /// Returns the next input value, or `None` to pause the machine until one is available.
+    fn input(&mut self) -> Option<i64>;
+    fn output(&mut self, value: i64);
+}
+
+/// Prompts on stdin for every input and prints every output. The end of stdin pauses the
+/// machine.
+pub struct Console;
+
+impl Io for Console {
+    fn input(&mut self) -> Option<i64> {
+        println!("Input: ");
+
+        let mut buffer = String::new();
+        if io::stdin().read_line(&mut buffer).unwrap() == 0 {
+            return None;
+        }
+
+        Some(buffer.trim().parse().unwrap())
+    }
+
+    fn output(&mut self, value: i64) {
+        println!("{}", value);
+    }
+}
+
+/// Queued inputs and collected outputs, for driving a machine from code.
+#[derive(Debug, Default, Clone)]
+pub struct Channels {
+    pub input: VecDeque<i64>,
+    pub output: Vec<i64>,
+}
+
+impl Channels {
+    pub fn new(input: &[i64]) -> Self {
+        Self {
+            input: input.iter().copied().collect(),
+            output: Vec::new(),
+        }
+    }
+}
+
+impl Io for Channels {
+    fn input(&mut self) -> Option<i64> {
+        self.input.pop_front()
+    }
+
+    fn output(&mut self, value: i64) {
+        self.output.push(value);
+    }
+}
+
+/// The complete state of a machine, so that it can be rewound with [`Intcode::restore`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot {
+    memory: Vec<i64>,
+    instruction_pointer: usize,
+    relative_base: i64,
+    steps: u64,
 }
 
 impl<'a> Intcode<'a> {
-    pub fn new(memory: &'a mut [i32]) -> Self {
+    pub fn new(memory: &'a mut [i64]) -> Self {
+        Intcode {
+            memory: Memory::new(memory),
+            instruction_pointer: 0,
+            relative_base: 0,
+            steps: 0,
+        }
+    }
+
+    /// Creates a machine that runs on top of `image` without modifying it, so that many
+    /// machines can share one copy of a program.
+    pub fn shared(image: Arc<[i64]>) -> Intcode<'static> {
         Intcode {
-            memory,
+            memory: Memory::shared(image),
             instruction_pointer: 0,
+            relative_base: 0,
+            steps: 0,
         }
     }
 
+    /// Reads the word at `address`.
+    pub fn peek(&self, address: usize) -> i64 {
+        self.memory.load(address)
+    }
+
+    /// Writes `value` to `address`, e.g. to patch a program before running it.
+    pub fn poke(&mut self, address: usize, value: i64) {
+        self.memory.store(address, value);
+    }
+
+    /// Runs until the program halts, reading input from stdin and printing output.
     pub fn run(&mut self) {
+        self.run_with(&mut Console);
+    }
+
+    /// Runs until the program halts or needs input that `io` can't provide yet.
+    pub fn run_with(&mut self, io: &mut impl Io) -> Status {
         loop {
-            let instruction = Instruction::read(&mut self.memory, self.instruction_pointer);
-
-            // uncomment when debugging
-            // let opcode = self.memory[self.instruction_pointer];
-            // println!(
-            //     "{} - {} - {:?}",
-            //     self.instruction_pointer, opcode, instruction
-            // );
-
-            match instruction {
-                Instruction::Add(param1, param2, param3) => {
-                    param3.set(param1.load() + param2.load());
-                    self.instruction_pointer += 4;
-                }
-                Instruction::Multiply(param1, param2, param3) => {
-                    param3.set(param1.load() * param2.load());
-                    self.instruction_pointer += 4;
-                }
-                Instruction::Input(location) => {
-                    println!("Input: ");
+            if let Some(status) = self.step(io) {
+                return status;
+            }
+        }
+    }
 
-                    let mut buffer = String::new();
-                    io::stdin().read_line(&mut buffer).unwrap();
+    /// Executes a single instruction. Returns `None` if the machine can keep going.
+    pub fn step(&mut self, io: &mut impl Io) -> Option<Status> {
+        let instruction = Instruction::read(&self.memory, self.instruction_pointer);
 
-                    let input: i32 = buffer.trim().parse().unwrap();
-                    location.set(input);
+        // uncomment when debugging
+        // let opcode = self.memory.load(self.instruction_pointer);
+        // println!(
+        //     "{} - {} - {:?}",
+        //     self.instruction_pointer, opcode, instruction
+        // );
 
-                    self.instruction_pointer += 2;
+        match instruction {
+            Instruction::Add(param1, param2, param3) => {
+                self.set(param3, self.load(param1) + self.load(param2));
+                self.instruction_pointer += 4;
+            }
+            Instruction::Multiply(param1, param2, param3) => {
+                self.set(param3, self.load(param1) * self.load(param2));
+                self.instruction_pointer += 4;
+            }
+            Instruction::Input(location) => {
+                let Some(input) = io.input() else {
+                    return Some(Status::AwaitingInput);
+                };
+                self.set(location, input);
+                self.instruction_pointer += 2;
+            }
+            Instruction::Output(location) => {
+                io.output(self.load(location));
+                self.instruction_pointer += 2;
+            }
+            Instruction::JumpIfTrue(condition, label) => {
+                if self.load(condition) != 0 {
+                    self.instruction_pointer = self.load(label) as usize;
+                } else {
+                    self.instruction_pointer += 3;
                 }
-                Instruction::Output(location) => {
-                    println!("{}", location.load());
-                    self.instruction_pointer += 2;
+            }
+            Instruction::JumpIfFalse(condition, label) => {
+                if self.load(condition) == 0 {
+                    self.instruction_pointer = self.load(label) as usize;
+                } else {
+                    self.instruction_pointer += 3;
                 }
-                Instruction::JumpIfTrue(condition, label) => {
-                    let condition = condition.load();
-                    if condition != 0 {
-                        self.instruction_pointer = label.load() as usize;
-                    } else {
-                        self.instruction_pointer += 3;
+            }
+            Instruction::LessThan(left, right, location) => {
+                let left = self.load(left);
+                let right = self.load(right);
+
+                self.set(location, if left < right { 1 } else { 0 });
+                self.instruction_pointer += 4;
+            }
+            Instruction::Equals(left, right, location) => {
+                let left = self.load(left);
+                let right = self.load(right);
+
+                self.set(location, if left == right { 1 } else { 0 });
+                self.instruction_pointer += 4;
+            }
+            Instruction::AdjustRelativeBase(offset) => {
+                self.relative_base += self.load(offset);
+                self.instruction_pointer += 2;
+            }
+            Instruction::Halt => return Some(Status::Halted),
+        }
+
+        self.steps += 1;
+        None
+    }
+
+    /// How many instructions the machine has executed.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            memory: self.memory.to_vec(),
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base,
+            steps: self.steps,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.memory.copy_from(&snapshot.memory);
+        self.instruction_pointer = snapshot.instruction_pointer;
+        self.relative_base = snapshot.relative_base;
+        self.steps = snapshot.steps;
+    }
+
+    fn address(&self, param: Parameter) -> usize {
+        let address = match param {
+            Parameter::Position(x) => x,
+            Parameter::Relative(x) => self.relative_base + x,
+            Parameter::Immediate(_) => panic!("Cannot write to an immediate parameter"),
+        };
+        usize::try_from(address).unwrap_or_else(|_| panic!("invalid address: {}", address))
+    }
+
+    fn load(&self, param: Parameter) -> i64 {
+        match param {
+            Parameter::Immediate(x) => x,
+            param => self.memory.load(self.address(param)),
+        }
+    }
+
+    fn set(&mut self, param: Parameter, value: i64) {
+        let address = self.address(param);
+        self.memory.store(address, value);
+    }
+}
+
+/// Number of words copied at once when a machine first writes to a shared image.
+const PAGE_SIZE: usize = 256;
+
+enum Memory<'a> {
+    /// The program image, plus zero-initialised memory past its end that the program may use.
+    Borrowed {
+        image: &'a mut [i64],
+        extended: Vec<i64>,
+    },
+    /// An image shared between machines. Pages are copied out of it the first time they are
+    /// written to, so the image itself never changes.
+    Shared {
+        image: Arc<[i64]>,
+        pages: Vec<Option<Box<[i64]>>>,
+    },
+}
+
+impl<'a> Memory<'a> {
+    fn new(image: &'a mut [i64]) -> Self {
+        Memory::Borrowed {
+            image,
+            extended: Vec::new(),
+        }
+    }
+
+    fn shared(image: Arc<[i64]>) -> Self {
+        Memory::Shared {
+            image,
+            pages: Vec::new(),
+        }
+    }
+
+    fn load(&self, address: usize) -> i64 {
+        match self {
+            Memory::Borrowed { image, extended } => match address.checked_sub(image.len()) {
+                None => image[address],
+                Some(offset) => extended.get(offset).copied().unwrap_or(0),
+            },
+            Memory::Shared { image, pages } => match pages.get(address / PAGE_SIZE) {
+                Some(Some(page)) => page[address % PAGE_SIZE],
+                _ => image.get(address).copied().unwrap_or(0),
+            },
+        }
+    }
+
+    fn store(&mut self, address: usize, value: i64) {
+        match self {
+            Memory::Borrowed { image, extended } => match address.checked_sub(image.len()) {
+                None => image[address] = value,
+                Some(offset) => {
+                    if offset >= extended.len() {
+                        extended.resize(offset + 1, 0);
                     }
+                    extended[offset] = value;
                 }
-                Instruction::JumpIfFalse(condition, label) => {
-                    let condition = condition.load();
-                    if condition == 0 {
-                        self.instruction_pointer = label.load() as usize;
-                    } else {
-                        self.instruction_pointer += 3;
-                    }
+            },
+            Memory::Shared { image, pages } => {
+                let index = address / PAGE_SIZE;
+                if index >= pages.len() {
+                    pages.resize(index + 1, None);
                 }
-                Instruction::LessThan(left, right, location) => {
-                    let left = left.load();
-                    let right = right.load();
+                let page = pages[index].get_or_insert_with(|| Self::page(image, index));
+                page[address % PAGE_SIZE] = value;
+            }
+        }
+    }
 
-                    location.set(if left < right { 1 } else { 0 });
-                    self.instruction_pointer += 4;
-                }
-                Instruction::Equals(left, right, location) => {
-                    let left = left.load();
-                    let right = right.load();
+    /// A copy of page `index` of `image`, padded with zeros.
+    fn page(image: &[i64], index: usize) -> Box<[i64]> {
+        let mut page = vec![0; PAGE_SIZE];
+        let start = (index * PAGE_SIZE).min(image.len());
+        let end = ((index + 1) * PAGE_SIZE).min(image.len());
+        page[..end - start].copy_from_slice(&image[start..end]);
+        page.into_boxed_slice()
+    }
 
-                    location.set(if left == right { 1 } else { 0 });
-                    self.instruction_pointer += 4;
-                }
-                Instruction::Halt => break,
+    fn to_vec(&self) -> Vec<i64> {
+        match self {
+            Memory::Borrowed { image, extended } => {
+                let mut memory = image.to_vec();
+                memory.extend_from_slice(extended);
+                memory
+            }
+            Memory::Shared { image, pages } => {
+                let written = pages.iter().rposition(|page| page.is_some());
+                let len = image.len().max(written.map_or(0, |i| (i + 1) * PAGE_SIZE));
+                (0..len).map(|address| self.load(address)).collect()
+            }
+        }
+    }
+
+    fn copy_from(&mut self, memory: &[i64]) {
+        match self {
+            Memory::Borrowed { image, extended } => {
+                let (head, tail) = memory.split_at(image.len());
+                image.copy_from_slice(head);
+                *extended = tail.to_vec();
+            }
+            Memory::Shared { image, pages } => {
+                *pages = memory
+                    .chunks(PAGE_SIZE)
+                    .enumerate()
+                    .map(|(index, chunk)| {
+                        let mut page = Self::page(image, index);
+                        if *chunk == page[..chunk.len()] {
+                            None
+                        } else {
+                            page[..chunk.len()].copy_from_slice(chunk);
+                            Some(page)
+                        }
+                    })
+                    .collect();
             }
         }
     }
@@ -94,12 +383,13 @@ enum Instruction {
     JumpIfFalse(Parameter, Parameter),
     LessThan(Parameter, Parameter, Parameter),
     Equals(Parameter, Parameter, Parameter),
+    AdjustRelativeBase(Parameter),
     Halt,
 }
 
 impl Instruction {
-    fn read(memory: &mut [i32], address: usize) -> Self {
-        let mem = memory[address];
+    fn read(memory: &Memory, address: usize) -> Self {
+        let mem = memory.load(address);
         let opcode = mem % 100;
 
         match opcode {
@@ -149,8 +439,12 @@ impl Instruction {
 
                 Instruction::Equals(param1, param2, param3)
             }
+            9 => {
+                let param = Parameter::get1(memory, address);
+                Instruction::AdjustRelativeBase(param)
+            }
             99 => Instruction::Halt,
-            _ => todo!(),
+            x => panic!("invalid opcode {} at address {}", x, address),
         }
     }
 }
@@ -176,68 +470,56 @@ impl Debug for Instruction {
             Instruction::Equals(x, y, z) => {
                 f.write_fmt(format_args!("Equals {:?} {:?} {:?}", x, y, z))
             }
+            Instruction::AdjustRelativeBase(x) => {
+                f.write_fmt(format_args!("AdjustRelativeBase {:?}", x))
+            }
             Instruction::Halt => f.write_str("Halt"),
         }
     }
 }
 
+#[derive(Clone, Copy)]
 enum Parameter {
-    Position(i32, *mut [i32]),
-    Immediate(i32),
+    Position(i64),
+    Immediate(i64),
+    Relative(i64),
 }
 
 impl Parameter {
-    fn get(memory: &mut [i32], mode: i32, param: i32) -> Self {
+    fn get(mode: i64, param: i64) -> Self {
         match mode {
-            0 => Parameter::Position(param, memory),
+            0 => Parameter::Position(param),
             1 => Parameter::Immediate(param),
+            2 => Parameter::Relative(param),
             x => panic!("invalid parameter mode: {}", x),
         }
     }
 
-    fn get1(memory: *mut [i32], address: usize) -> Self {
-        let memory: &mut [i32] = unsafe { memory.as_mut().unwrap() };
-        let mode = (memory[address] / 100) % 10;
-        let param = memory[address + 1];
-        Parameter::get(memory, mode, param)
+    fn get1(memory: &Memory, address: usize) -> Self {
+        let mode = (memory.load(address) / 100) % 10;
+        let param = memory.load(address + 1);
+        Parameter::get(mode, param)
     }
 
-    fn get2(memory: *mut [i32], address: usize) -> Self {
-        let memory: &mut [i32] = unsafe { memory.as_mut().unwrap() };
-        let mode = (memory[address] / 1_000) % 10;
-        let param = memory[address + 2];
-        Parameter::get(memory, mode, param)
+    fn get2(memory: &Memory, address: usize) -> Self {
+        let mode = (memory.load(address) / 1_000) % 10;
+        let param = memory.load(address + 2);
+        Parameter::get(mode, param)
     }
 
-    fn get3(memory: *mut [i32], address: usize) -> Self {
-        let memory: &mut [i32] = unsafe { memory.as_mut().unwrap() };
-        let mode = (memory[address] / 10_000) % 10;
-        let param = memory[address + 3];
-        Parameter::get(memory, mode, param)
-    }
-
-    fn load(&self) -> i32 {
-        match self {
-            Parameter::Position(x, memory) => unsafe { memory.as_mut().unwrap()[*x as usize] },
-            Parameter::Immediate(x) => *x,
-        }
-    }
-
-    fn set(&self, value: i32) {
-        match self {
-            Parameter::Position(x, memory) => unsafe {
-                memory.as_mut().unwrap()[*x as usize] = value
-            },
-            Parameter::Immediate(_) => panic!("Cannot write to an immediate parameter"),
-        }
+    fn get3(memory: &Memory, address: usize) -> Self {
+        let mode = (memory.load(address) / 10_000) % 10;
+        let param = memory.load(address + 3);
+        Parameter::get(mode, param)
     }
 }
 
 impl Debug for Parameter {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
         match self {
-            Parameter::Position(x, _) => f.write_fmt(format_args!("{}({})", self.load(), x)),
-            Parameter::Immediate(_) => f.write_fmt(format_args!("{}", self.load())),
+            Parameter::Position(x) => f.write_fmt(format_args!("[{}]", x)),
+            Parameter::Immediate(x) => f.write_fmt(format_args!("{}", x)),
+            Parameter::Relative(x) => f.write_fmt(format_args!("[rb{:+}]", x)),
         }
     }
 }
@@ -421,4 +703,116 @@ mod tests {
         intcode.run();
         assert_eq!(memory, [0, 1, 4, 0, 99]);
     }
+
+    #[test]
+    fn run_with_channels() {
+        let mut memory = [3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::new(&[8]);
+        assert_eq!(intcode.run_with(&mut channels), Status::Halted);
+        assert_eq!(channels.output, [1]);
+    }
+
+    #[test]
+    fn run_with_awaiting_input() {
+        let mut memory = [3, 7, 4, 7, 1105, 1, 0, 0];
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::default();
+        assert_eq!(intcode.run_with(&mut channels), Status::AwaitingInput);
+        assert!(channels.output.is_empty());
+
+        channels.input.push_back(42);
+        assert_eq!(intcode.run_with(&mut channels), Status::AwaitingInput);
+        assert_eq!(channels.output, [42]);
+        assert_eq!(intcode.steps(), 3);
+    }
+
+    #[test]
+    fn run_relative_base_quine() {
+        let program = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let mut memory = program;
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::default();
+        intcode.run_with(&mut channels);
+        assert_eq!(channels.output, program);
+    }
+
+    #[test]
+    fn run_large_numbers() {
+        let mut memory = [1102, 34915192, 34915192, 7, 4, 7, 99, 0];
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::default();
+        intcode.run_with(&mut channels);
+        assert_eq!(channels.output, [1219070632396864]);
+
+        let mut memory = [104, 1125899906842624, 99];
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::default();
+        intcode.run_with(&mut channels);
+        assert_eq!(channels.output, [1125899906842624]);
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut memory = [3, 20, 1, 20, 20, 20, 4, 20, 99];
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::default();
+        intcode.run_with(&mut channels);
+        let snapshot = intcode.snapshot();
+
+        channels.input.push_back(5);
+        intcode.run_with(&mut channels);
+        intcode.restore(&snapshot);
+        channels.input.push_back(7);
+        intcode.run_with(&mut channels);
+        assert_eq!(channels.output, [10, 14]);
+    }
+
+    #[test]
+    fn run_shared() {
+        let image: Arc<[i64]> = Arc::from([1, 0, 0, 0, 99]);
+        let mut intcode = Intcode::shared(image.clone());
+        intcode.run();
+        assert_eq!(intcode.peek(0), 2);
+        assert_eq!(*image, [1, 0, 0, 0, 99]);
+    }
+
+    #[test]
+    fn run_shared_past_image() {
+        let image: Arc<[i64]> = Arc::from([1101, 20, 22, 300, 4, 300, 99]);
+        let mut intcode = Intcode::shared(image);
+        let mut channels = Channels::default();
+        intcode.run_with(&mut channels);
+        assert_eq!(channels.output, [42]);
+        assert_eq!(intcode.peek(1000), 0);
+    }
+
+    #[test]
+    fn poke_shared() {
+        let image: Arc<[i64]> = Arc::from([1, 0, 0, 0, 99]);
+        let mut intcode = Intcode::shared(image);
+        intcode.poke(1, 4);
+        intcode.poke(2, 4);
+        intcode.run();
+        assert_eq!(intcode.peek(0), 198);
+    }
+
+    #[test]
+    fn snapshot_and_restore_shared() {
+        let image: Arc<[i64]> = Arc::from([3, 20, 1, 20, 20, 20, 4, 20, 99]);
+        let mut intcode = Intcode::shared(image);
+        let mut channels = Channels::default();
+        intcode.run_with(&mut channels);
+        let snapshot = intcode.snapshot();
+
+        channels.input.push_back(5);
+        intcode.run_with(&mut channels);
+        intcode.restore(&snapshot);
+        assert_eq!(intcode.snapshot(), snapshot);
+        channels.input.push_back(7);
+        intcode.run_with(&mut channels);
+        assert_eq!(channels.output, [10, 14]);
+    }
 }
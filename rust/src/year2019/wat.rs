@@ -0,0 +1,525 @@
+use std::fmt::Write;
+
+/// Words of zero-initialised memory the module reserves past the end of the program.
+const HEAP_WORDS: usize = 64 * 1024;
+
+const WASM_PAGE_SIZE: usize = 64 * 1024;
+
+/// The instructions in the dispatch table, indexed by opcode.
+const OPCODES: [&str; 10] = [
+    "$invalid", "$add", "$mul", "$in", "$out", "$jt", "$jf", "$lt", "$eq", "$rb",
+];
+
+/// Translates an Intcode program into a WebAssembly text module.
+///
+/// The module imports `env.input: () -> i64` and `env.output: (i64) -> ()`, and exports its
+/// `memory` and a `run` function that runs the program until it halts. Intcode programs may
+/// modify their own instructions, so the program becomes the module's initial memory and `run`
+/// decodes each instruction as it reaches it, one 64-bit word per address.
+pub fn translate(program: &[i64]) -> String {
+    let bytes = (program.len() + HEAP_WORDS) * 8;
+    let pages = bytes.div_ceil(WASM_PAGE_SIZE);
+
+    let mut data = String::new();
+    for byte in program.iter().flat_map(|word| word.to_le_bytes()) {
+        write!(data, "\\{:02x}", byte).unwrap();
+    }
+
+    let mut wat = String::new();
+    wat.push_str("(module\n");
+    wat.push_str("  (import \"env\" \"input\" (func $input (result i64)))\n");
+    wat.push_str("  (import \"env\" \"output\" (func $output (param i64)))\n");
+    writeln!(wat, "  (memory (export \"memory\") {})", pages).unwrap();
+    writeln!(wat, "  (data (i32.const 0) \"{}\")", data).unwrap();
+    wat.push_str("  (func $run (export \"run\")\n");
+    wat.push_str("    (local $ip i64) (local $rb i64) (local $op i64)\n");
+    wat.push_str("    (local $p1 i64) (local $p2 i64) (local $p3 i64)\n");
+
+    let mut body = Body::default();
+    body.run();
+    for line in body.lines {
+        writeln!(wat, "    {}", line).unwrap();
+    }
+
+    wat.push_str("  )\n");
+    wat.push_str(")\n");
+    wat
+}
+
+/// The flat instruction sequence of the `run` function.
+#[derive(Default)]
+struct Body {
+    lines: Vec<String>,
+    depth: usize,
+}
+
+impl Body {
+    fn emit(&mut self, instruction: &str) {
+        let indent = "  ".repeat(self.depth);
+        self.lines.push(format!("{}{}", indent, instruction));
+    }
+
+    fn open(&mut self, instruction: &str) {
+        self.emit(instruction);
+        self.depth += 1;
+    }
+
+    fn end(&mut self) {
+        self.depth -= 1;
+        self.emit("end");
+    }
+
+    fn run(&mut self) {
+        self.open("block $halt");
+        self.open("loop $next");
+
+        self.emit("local.get $ip");
+        self.load();
+        self.emit("local.set $op");
+
+        self.opcode();
+        self.emit("i64.const 99");
+        self.emit("i64.eq");
+        self.emit("br_if $halt");
+
+        for (depth, label) in OPCODES.iter().enumerate().rev() {
+            if depth == 0 {
+                continue;
+            }
+            self.open(&format!("block {}", label));
+        }
+        self.open("block $invalid");
+        self.opcode();
+        self.emit("i32.wrap_i64");
+        self.emit(&format!("br_table {} $invalid", OPCODES.join(" ")));
+        self.end();
+        self.emit("unreachable");
+
+        for label in &OPCODES[1..] {
+            self.end();
+            self.emit(&format!(";; {}", &label[1..]));
+            self.instruction(label);
+            self.emit("br $next");
+        }
+
+        self.end();
+        self.end();
+    }
+
+    fn instruction(&mut self, label: &str) {
+        match label {
+            "$add" | "$mul" => {
+                self.parameters(3);
+                self.emit("local.get $p3");
+                self.address();
+                self.emit("local.get $p1");
+                self.load();
+                self.emit("local.get $p2");
+                self.load();
+                self.emit(if label == "$add" {
+                    "i64.add"
+                } else {
+                    "i64.mul"
+                });
+                self.emit("i64.store");
+                self.advance(4);
+            }
+            "$in" => {
+                self.parameters(1);
+                self.emit("local.get $p1");
+                self.address();
+                self.emit("call $input");
+                self.emit("i64.store");
+                self.advance(2);
+            }
+            "$out" => {
+                self.parameters(1);
+                self.emit("local.get $p1");
+                self.load();
+                self.emit("call $output");
+                self.advance(2);
+            }
+            "$jt" | "$jf" => {
+                self.parameters(2);
+                self.emit("local.get $p2");
+                self.load();
+                self.emit("local.get $ip");
+                self.emit("i64.const 3");
+                self.emit("i64.add");
+                self.emit("local.get $p1");
+                self.load();
+                self.emit("i64.const 0");
+                self.emit(if label == "$jt" { "i64.ne" } else { "i64.eq" });
+                self.emit("select");
+                self.emit("local.set $ip");
+            }
+            "$lt" | "$eq" => {
+                self.parameters(3);
+                self.emit("local.get $p3");
+                self.address();
+                self.emit("local.get $p1");
+                self.load();
+                self.emit("local.get $p2");
+                self.load();
+                self.emit(if label == "$lt" { "i64.lt_s" } else { "i64.eq" });
+                self.emit("i64.extend_i32_u");
+                self.emit("i64.store");
+                self.advance(4);
+            }
+            "$rb" => {
+                self.parameters(1);
+                self.emit("local.get $rb");
+                self.emit("local.get $p1");
+                self.load();
+                self.emit("i64.add");
+                self.emit("local.set $rb");
+                self.advance(2);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Pushes `$op % 100`.
+    fn opcode(&mut self) {
+        self.emit("local.get $op");
+        self.emit("i64.const 100");
+        self.emit("i64.rem_s");
+    }
+
+    /// Resolves the address each of the first `count` parameters refers to into `$p1`, `$p2`
+    /// and `$p3`. An immediate parameter refers to its own address.
+    fn parameters(&mut self, count: usize) {
+        for n in 1..=count {
+            // immediate
+            self.emit("local.get $ip");
+            self.emit(&format!("i64.const {}", n));
+            self.emit("i64.add");
+            // relative
+            self.emit("local.get $rb");
+            self.raw(n);
+            self.emit("i64.add");
+            // position
+            self.raw(n);
+
+            self.mode(n);
+            self.emit("i64.const 2");
+            self.emit("i64.eq");
+            self.emit("select");
+            self.mode(n);
+            self.emit("i64.const 1");
+            self.emit("i64.eq");
+            self.emit("select");
+            self.emit(&format!("local.set $p{}", n));
+        }
+    }
+
+    /// Pushes the word stored in parameter `n` of the current instruction.
+    fn raw(&mut self, n: usize) {
+        self.emit("local.get $ip");
+        self.emit(&format!("i64.const {}", n));
+        self.emit("i64.add");
+        self.load();
+    }
+
+    /// Pushes the mode of parameter `n` of the current instruction.
+    fn mode(&mut self, n: usize) {
+        self.emit("local.get $op");
+        self.emit(&format!("i64.const {}", 10i64.pow(n as u32 + 1)));
+        self.emit("i64.div_s");
+        self.emit("i64.const 10");
+        self.emit("i64.rem_s");
+    }
+
+    /// Turns the word address on the stack into a byte address.
+    fn address(&mut self) {
+        self.emit("i64.const 8");
+        self.emit("i64.mul");
+        self.emit("i32.wrap_i64");
+    }
+
+    /// Replaces the word address on the stack with the word stored there.
+    fn load(&mut self) {
+        self.address();
+        self.emit("i64.load");
+    }
+
+    fn advance(&mut self, length: i64) {
+        self.emit("local.get $ip");
+        self.emit(&format!("i64.const {}", length));
+        self.emit("i64.add");
+        self.emit("local.set $ip");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year2019::vm::{parse_program, Channels, Intcode};
+    use std::collections::{HashMap, VecDeque};
+
+    /// The pieces of a module, checked against the shape [`translate`] promises.
+    struct Module<'a> {
+        memory: Vec<u8>,
+        body: Vec<Vec<&'a str>>,
+    }
+
+    impl<'a> Module<'a> {
+        fn parse(wat: &'a str) -> Self {
+            assert_balanced(wat);
+            assert!(wat.starts_with("(module\n"));
+            assert!(wat.contains("(import \"env\" \"input\" (func $input (result i64)))"));
+            assert!(wat.contains("(import \"env\" \"output\" (func $output (param i64)))"));
+            assert!(wat.contains("(func $run (export \"run\")"));
+
+            let pages = wat
+                .lines()
+                .find_map(|l| l.trim().strip_prefix("(memory (export \"memory\") "))
+                .and_then(|l| l.strip_suffix(')'))
+                .and_then(|l| l.parse::<usize>().ok())
+                .expect("memory declaration");
+
+            let data = wat
+                .lines()
+                .find_map(|l| l.trim().strip_prefix("(data (i32.const 0) \""))
+                .and_then(|l| l.strip_suffix("\")"))
+                .expect("data segment");
+            let mut memory = data
+                .split('\\')
+                .skip(1)
+                .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+                .collect::<Vec<_>>();
+            assert!(memory.len() <= pages * WASM_PAGE_SIZE);
+            memory.resize(pages * WASM_PAGE_SIZE, 0);
+
+            let body = wat
+                .lines()
+                .skip_while(|l| !l.contains("(func $run"))
+                .skip(1)
+                .map(|l| l.trim())
+                .filter(|l| !l.starts_with("(local") && !l.starts_with(";;"))
+                .take_while(|l| *l != ")")
+                .map(|l| l.split_whitespace().collect())
+                .collect();
+
+            Self { memory, body }
+        }
+
+        fn word(&self, address: usize) -> i64 {
+            let bytes = self.memory[address * 8..address * 8 + 8]
+                .try_into()
+                .unwrap();
+            i64::from_le_bytes(bytes)
+        }
+
+        /// Evaluates the flat instruction subset that [`translate`] emits.
+        fn run(&mut self, input: &[i64]) -> Vec<i64> {
+            let mut ends = HashMap::new();
+            let mut opened = Vec::new();
+            for (i, instruction) in self.body.iter().enumerate() {
+                match instruction[0] {
+                    "block" | "loop" => opened.push(i),
+                    "end" => {
+                        ends.insert(opened.pop().unwrap(), i);
+                    }
+                    _ => {}
+                }
+            }
+            assert!(opened.is_empty());
+
+            let mut input = input.iter().copied().collect::<VecDeque<_>>();
+            let mut output = Vec::new();
+            let mut locals: HashMap<&str, i64> = HashMap::new();
+            let mut stack: Vec<i64> = Vec::new();
+            let mut control: Vec<(&str, usize)> = Vec::new();
+            let mut pc = 0;
+
+            while pc < self.body.len() {
+                let instruction = self.body[pc].clone();
+                pc += 1;
+
+                let target = match instruction[0] {
+                    "block" | "loop" => {
+                        control.push((instruction[1], pc - 1));
+                        None
+                    }
+                    "end" => {
+                        control.pop();
+                        None
+                    }
+                    "br" => Some(instruction[1]),
+                    "br_if" => (stack.pop().unwrap() != 0).then_some(instruction[1]),
+                    "br_table" => {
+                        let index = stack.pop().unwrap() as u32 as usize;
+                        let labels = &instruction[1..];
+                        Some(labels[index.min(labels.len() - 1)])
+                    }
+                    "unreachable" => panic!("unreachable executed"),
+                    "local.get" => {
+                        stack.push(locals.get(instruction[1]).copied().unwrap_or(0));
+                        None
+                    }
+                    "local.set" => {
+                        let value = stack.pop().unwrap();
+                        locals.insert(instruction[1], value);
+                        None
+                    }
+                    "i64.const" => {
+                        stack.push(instruction[1].parse().unwrap());
+                        None
+                    }
+                    "i64.load" => {
+                        let address = stack.pop().unwrap() as u32 as usize;
+                        stack.push(self.word(address / 8));
+                        None
+                    }
+                    "i64.store" => {
+                        let value = stack.pop().unwrap();
+                        let address = stack.pop().unwrap() as u32 as usize;
+                        self.memory[address..address + 8].copy_from_slice(&value.to_le_bytes());
+                        None
+                    }
+                    "call" => {
+                        match instruction[1] {
+                            "$input" => stack.push(input.pop_front().unwrap()),
+                            "$output" => output.push(stack.pop().unwrap()),
+                            f => panic!("unknown function {}", f),
+                        }
+                        None
+                    }
+                    "select" => {
+                        let condition = stack.pop().unwrap();
+                        let b = stack.pop().unwrap();
+                        let a = stack.pop().unwrap();
+                        stack.push(if condition != 0 { a } else { b });
+                        None
+                    }
+                    "i32.wrap_i64" => {
+                        let value = stack.pop().unwrap();
+                        stack.push(value as i32 as i64);
+                        None
+                    }
+                    "i64.extend_i32_u" => {
+                        let value = stack.pop().unwrap();
+                        stack.push(value as u32 as i64);
+                        None
+                    }
+                    op => {
+                        let b = stack.pop().unwrap();
+                        let a = stack.pop().unwrap();
+                        stack.push(match op {
+                            "i64.add" => a.wrapping_add(b),
+                            "i64.mul" => a.wrapping_mul(b),
+                            "i64.div_s" => a / b,
+                            "i64.rem_s" => a % b,
+                            "i64.eq" => (a == b) as i64,
+                            "i64.ne" => (a != b) as i64,
+                            "i64.lt_s" => (a < b) as i64,
+                            op => panic!("unsupported instruction {}", op),
+                        });
+                        None
+                    }
+                };
+
+                if let Some(label) = target {
+                    let start = loop {
+                        let (name, start) = control.pop().expect("branch to unknown label");
+                        if name == label {
+                            break start;
+                        }
+                    };
+                    if self.body[start][0] == "loop" {
+                        control.push((label, start));
+                        pc = start + 1;
+                    } else {
+                        pc = ends[&start] + 1;
+                    }
+                }
+            }
+
+            assert!(stack.is_empty());
+            output
+        }
+    }
+
+    fn assert_balanced(wat: &str) {
+        let mut depth = 0;
+        let mut in_string = false;
+        for c in wat.chars() {
+            match c {
+                '"' => in_string = !in_string,
+                '(' if !in_string => depth += 1,
+                ')' if !in_string => {
+                    depth -= 1;
+                    assert!(depth >= 0, "unbalanced parentheses");
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(depth, 0, "unbalanced parentheses");
+        assert!(!in_string, "unterminated string");
+    }
+
+    /// Runs `program` in the interpreter and as a module, and checks they agree.
+    fn assert_same(program: &[i64], input: &[i64]) -> Vec<i64> {
+        let mut memory = program.to_vec();
+        let mut intcode = Intcode::new(&mut memory);
+        let mut channels = Channels::new(input);
+        intcode.run_with(&mut channels);
+
+        let wat = translate(program);
+        let mut module = Module::parse(&wat);
+        for (address, word) in program.iter().enumerate() {
+            assert_eq!(module.word(address), *word);
+        }
+
+        let output = module.run(input);
+        assert_eq!(output, channels.output);
+        for (address, word) in memory.iter().enumerate() {
+            assert_eq!(module.word(address), *word, "memory differs at {}", address);
+        }
+        output
+    }
+
+    #[test]
+    fn test_add_and_multiply() {
+        assert_same(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50], &[]);
+    }
+
+    #[test]
+    fn test_compare_with_8() {
+        let program = [
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+        assert_eq!(assert_same(&program, &[7]), [999]);
+        assert_eq!(assert_same(&program, &[8]), [1000]);
+        assert_eq!(assert_same(&program, &[9]), [1001]);
+    }
+
+    #[test]
+    fn test_relative_base_quine() {
+        let program = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        assert_eq!(assert_same(&program, &[]), program);
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        assert_same(&[1102, 34915192, 34915192, 7, 4, 7, 99, 0], &[]);
+        assert_same(&[104, 1125899906842624, 99], &[]);
+    }
+
+    #[test]
+    fn test_self_modifying() {
+        assert_same(&[1002, 4, 3, 4, 33], &[]);
+    }
+
+    #[test]
+    fn test_day05() {
+        let program = parse_program(include_str!("../../data/year2019/day05.txt")).unwrap();
+        assert_same(&program, &[1]);
+        assert_same(&program, &[5]);
+    }
+}
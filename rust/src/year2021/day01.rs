@@ -0,0 +1,74 @@
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+/// Sonar Sweep: how often the depth increases, one measurement at a time and then over windows of
+/// three.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2021::day01::Day01;
+///
+/// let depths = Day01::parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263")?;
+/// assert_eq!(Day01::part1(&depths), 7);
+/// assert_eq!(Day01::part2(&depths), 5);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input<'a> = Vec<u32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
+
+    fn part1(depths: &Self::Input<'_>) -> usize {
+        count_increases(depths, 1)
+    }
+
+    fn part2(depths: &Self::Input<'_>) -> usize {
+        count_increases(depths, 3)
+    }
+}
+
+fn parse_data(data: &str) -> Result<Vec<u32>, ParseError> {
+    data.lines().map(|line| error::number(data, line)).collect()
+}
+
+/// Counts the sliding windows of `window` depths whose sum is larger than the one before. Two
+/// windows next to each other share all but their first and last depths, so comparing those
+/// is enough.
+pub fn count_increases(depths: &[u32], window: usize) -> usize {
+    depths
+        .iter()
+        .zip(depths.iter().skip(window))
+        .filter(|(first, last)| last > first)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "\
+199
+200
+208
+210
+200
+207
+240
+269
+260
+263";
+
+    #[test]
+    fn test_count_increases() {
+        let depths = parse_data(TEST_DATA).unwrap();
+        assert_eq!(count_increases(&depths, 1), 7);
+        assert_eq!(count_increases(&depths, 3), 5);
+        assert_eq!(count_increases(&depths[..3], 3), 0);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+/// One step of the planned course.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Command {
+    Forward(i32),
+    Down(i32),
+    Up(i32),
+}
+
+/// Dive!: where the submarine ends up after the planned course, as position times depth.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2021::day02::Day02;
+///
+/// let course = Day02::parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2")?;
+/// assert_eq!(Day02::part1(&course), 150);
+/// assert_eq!(Day02::part2(&course), 900);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input<'a> = Vec<Command>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
+
+    fn part1(commands: &Self::Input<'_>) -> i32 {
+        let (position, depth) = dive(commands);
+        position * depth
+    }
+
+    fn part2(commands: &Self::Input<'_>) -> i32 {
+        let (position, depth) = dive_with_aim(commands);
+        position * depth
+    }
+}
+
+fn parse_data(data: &str) -> Result<Vec<Command>, ParseError> {
+    data.lines()
+        .map(|line| {
+            let (direction, units) = error::split_once(data, line, ' ')?;
+            let units = error::number(data, units)?;
+            match direction {
+                "forward" => Ok(Command::Forward(units)),
+                "down" => Ok(Command::Down(units)),
+                "up" => Ok(Command::Up(units)),
+                _ => {
+                    let message = format!("expected forward, down or up, found {:?}", direction);
+                    Err(ParseError::at(data, direction, message))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Where the submarine ends up when down and up change its depth.
+pub fn dive(commands: &[Command]) -> (i32, i32) {
+    commands
+        .iter()
+        .fold((0, 0), |(position, depth), command| match *command {
+            Command::Forward(units) => (position + units, depth),
+            Command::Down(units) => (position, depth + units),
+            Command::Up(units) => (position, depth - units),
+        })
+}
+
+/// Where the submarine ends up when down and up change its aim, and moving forward changes its
+/// depth by the aim.
+pub fn dive_with_aim(commands: &[Command]) -> (i32, i32) {
+    let (position, depth, _) = commands.iter().fold(
+        (0, 0, 0),
+        |(position, depth, aim), command| match *command {
+            Command::Forward(units) => (position + units, depth + aim * units, aim),
+            Command::Down(units) => (position, depth, aim + units),
+            Command::Up(units) => (position, depth, aim - units),
+        },
+    );
+    (position, depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "\
+forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2";
+
+    #[test]
+    fn test_parse_data() {
+        let commands = parse_data(TEST_DATA).unwrap();
+        assert_eq!(commands[..2], [Command::Forward(5), Command::Down(5)]);
+        assert_eq!(
+            parse_data("forward 5\nback 2"),
+            Err(ParseError::new(
+                2,
+                1,
+                "expected forward, down or up, found \"back\""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_dive() {
+        let commands = parse_data(TEST_DATA).unwrap();
+        assert_eq!(dive(&commands), (15, 10));
+    }
+
+    #[test]
+    fn test_dive_with_aim() {
+        let commands = parse_data(TEST_DATA).unwrap();
+        assert_eq!(dive_with_aim(&commands), (15, 60));
+    }
+}
@@ -1,30 +1,50 @@
-const DATA: &str = include_str!("../../data/year2022/day01.txt");
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run_part1() {
-    let input = parse_data(DATA);
-    println!("most calories: {}", get_most_calories(&input));
-}
+/// Calorie Counting: the most calories one elf carries, and the most three elves carry.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2022::day01::Day01;
+///
+/// let elves = Day01::parse("1000\n2000\n\n4000\n\n5000\n6000")?;
+/// assert_eq!(Day01::part1(&elves), 11000);
+/// assert_eq!(Day01::part2(&elves), 18000);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input<'a> = Vec<Vec<i32>>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
 
-pub fn run_part2() {
-    let input = parse_data(DATA);
-    println!("top 3: {}", sum_top_three_calories(&input));
+    fn part1(input: &Self::Input<'_>) -> i32 {
+        get_most_calories(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i32 {
+        sum_top_three_calories(input)
+    }
 }
 
-fn parse_data(data: &str) -> Vec<Vec<i32>> {
+fn parse_data(data: &str) -> Result<Vec<Vec<i32>>, ParseError> {
     data.split("\n\n")
-        .map(|s| {
-            s.lines()
-                .map(|l| l.parse::<i32>().unwrap())
-                .collect::<Vec<_>>()
-        })
+        .map(|s| s.lines().map(|l| error::number(data, l)).collect())
         .collect()
 }
 
-fn get_most_calories(input: &Vec<Vec<i32>>) -> i32 {
+/// The most calories carried by one elf.
+pub fn get_most_calories(input: &[Vec<i32>]) -> i32 {
     input.iter().map(|elf| elf.iter().sum()).max().unwrap()
 }
 
-fn sum_top_three_calories(input: &Vec<Vec<i32>>) -> i32 {
+/// The calories carried by the three elves carrying the most.
+pub fn sum_top_three_calories(input: &[Vec<i32>]) -> i32 {
     let mut elves = input
         .iter()
         .map(|elf| elf.iter().sum())
@@ -55,14 +75,14 @@ mod tests {
 
     #[test]
     fn test_get_most_calories() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         let calories = get_most_calories(&input);
         assert_eq!(calories, 24000);
     }
 
     #[test]
     fn test_sum_top_three_calories() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         let calories = sum_top_three_calories(&input);
         assert_eq!(calories, 45000);
     }
@@ -1,15 +1,36 @@
-const DATA: &str = include_str!("../../data/year2022/day02.txt");
+use crate::error::ParseError;
+use crate::solution::Solution;
 
-pub fn run_part1() {
-    let input = parse_data(DATA);
-    let result = score_following_strategy_guide(&input);
-    println!("Result: {}", result);
-}
+/// Rock Paper Scissors: the total score from following the strategy guide, reading the second
+/// column first as the shape to play and then as how the round should end.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2022::day02::Day02;
+///
+/// let guide = Day02::parse("A Y\nB X\nC Z")?;
+/// assert_eq!(Day02::part1(&guide), 15);
+/// assert_eq!(Day02::part2(&guide), 12);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input<'a> = Vec<(&'a str, &'a str)>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
 
-pub fn run_part2() {
-    let input = parse_data(DATA);
-    let result = score_correct_strategy_guide(&input);
-    println!("Result: {}", result);
+    fn part1(input: &Self::Input<'_>) -> i32 {
+        score_following_strategy_guide(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i32 {
+        score_correct_strategy_guide(input)
+    }
 }
 
 fn score_following_strategy_guide(input: &[(&str, &str)]) -> i32 {
@@ -26,7 +47,8 @@ fn score_correct_strategy_guide(input: &[(&str, &str)]) -> i32 {
         .sum()
 }
 
-fn round_score(opp: &str, player: &str) -> i32 {
+/// The score for playing `player` (X, Y or Z) against `opp` (A, B or C).
+pub fn round_score(opp: &str, player: &str) -> i32 {
     let shape = match player {
         "X" => 1,
         "Y" => 2,
@@ -41,7 +63,9 @@ fn round_score(opp: &str, player: &str) -> i32 {
     shape + outcome
 }
 
-fn correct_round_scoring(opp: &str, outcome: &str) -> i32 {
+/// The score for a round against `opp` that ends the way `outcome` says: X to lose, Y to draw and
+/// Z to win.
+pub fn correct_round_scoring(opp: &str, outcome: &str) -> i32 {
     match outcome {
         "X" => calc_loss(opp),
         "Y" => calc_draw(opp),
@@ -51,7 +75,7 @@ fn correct_round_scoring(opp: &str, outcome: &str) -> i32 {
 }
 
 fn calc_loss(opp: &str) -> i32 {
-    0 + match opp {
+    match opp {
         "A" => 3,
         "B" => 1,
         "C" => 2,
@@ -77,10 +101,22 @@ fn calc_win(opp: &str) -> i32 {
     }
 }
 
-fn parse_data(data: &str) -> Vec<(&str, &str)> {
+fn parse_data(data: &str) -> Result<Vec<(&str, &str)>, ParseError> {
     data.lines()
-        .map(|line| line.split(' ').collect::<Vec<_>>())
-        .map(|line| (line[0], line[1]))
+        .map(|line| {
+            let Some((opp, player)) = line.split_once(' ') else {
+                return Err(ParseError::after(data, line, "expected two columns"));
+            };
+            if !matches!(opp, "A" | "B" | "C") {
+                let message = format!("expected A, B or C, found {:?}", opp);
+                return Err(ParseError::at(data, opp, message));
+            }
+            if !matches!(player, "X" | "Y" | "Z") {
+                let message = format!("expected X, Y or Z, found {:?}", player);
+                return Err(ParseError::at(data, player, message));
+            }
+            Ok((opp, player))
+        })
         .collect()
 }
 
@@ -95,21 +131,26 @@ C Z";
 
     #[test]
     fn test_parse_data() {
-        let res = parse_data(TEST_DATA);
+        let res = parse_data(TEST_DATA).unwrap();
         let expected = vec![("A", "Y"), ("B", "X"), ("C", "Z")];
         assert_eq!(res, expected);
+
+        assert_eq!(
+            parse_data("A Y\nB W"),
+            Err(ParseError::new(2, 3, "expected X, Y or Z, found \"W\""))
+        );
     }
 
     #[test]
     fn test_score_following_strategy_guide() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         let result = score_following_strategy_guide(&input);
         assert_eq!(result, 15);
     }
 
     #[test]
     fn test_score_correct_strategy_guide() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         let result = score_correct_strategy_guide(&input);
         assert_eq!(result, 12);
     }
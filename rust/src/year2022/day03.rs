@@ -1,30 +1,118 @@
+use crate::anonymise::Rng;
+use crate::error::ParseError;
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-const DATA: &str = include_str!("../../data/year2022/day03.txt");
+/// Rucksack Reorganization: the priorities of the items in both compartments of each rucksack,
+/// and of the badge each group of three elves shares.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2022::day03::Day03;
+///
+/// let rucksacks = Day03::parse(
+///     "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+///      jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+///      PmmdzqPrVvPwwTWBwg\n\
+///      wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+///      ttgJtRGJQctTZtZT\n\
+///      CrZsJsPPZsGzwwsLwLmpwMDw",
+/// )?;
+/// assert_eq!(Day03::part1(&rucksacks), 157);
+/// assert_eq!(Day03::part2(&rucksacks), 70);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day03;
 
-pub fn run_part1() {
-    let priority: i32 = DATA.lines().map(|x| rucksack_priority(x)).sum();
-    println!("priority: {}", priority);
-}
-pub fn run_part2() {
-    let mut iter = DATA.lines();
-    let mut priorities = 0;
-
-    while let Some(line) = iter.next() {
-        let mut group = Vec::new();
-        group.push(line);
-        let line = iter.next().unwrap();
-        group.push(line);
-        let line = iter.next().unwrap();
-        group.push(line);
-        let item_type = badge_item_type(&group);
-        priorities += get_priority(item_type);
+impl Solution for Day03 {
+    type Input<'a> = Vec<&'a str>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        let rucksacks = data
+            .lines()
+            .map(|line| match line.find(|c: char| !c.is_ascii_alphabetic()) {
+                Some(i) => Err(ParseError::at(data, &line[i..], "expected a letter")),
+                None if rucksack_priority(line).is_none() => Err(ParseError::at(
+                    data,
+                    line,
+                    "expected an item in both compartments",
+                )),
+                None => Ok(line),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for group in rucksacks.chunks(3) {
+            if group.len() < 3 {
+                let message = "expected a group of three rucksacks";
+                return Err(ParseError::after(data, group[group.len() - 1], message));
+            }
+            if badge_item_type(group).is_none() {
+                let message = "expected the group to share exactly one item";
+                return Err(ParseError::at(data, group[0], message));
+            }
+        }
+        Ok(rucksacks)
     }
 
-    println!("Priorities: {}", priorities);
+    fn part1(rucksacks: &Self::Input<'_>) -> i32 {
+        rucksacks
+            .iter()
+            .map(|r| rucksack_priority(r).unwrap())
+            .sum()
+    }
+
+    fn part2(rucksacks: &Self::Input<'_>) -> i32 {
+        rucksacks
+            .chunks(3)
+            .map(|group| get_priority(badge_item_type(group).unwrap()))
+            .sum()
+    }
+
+    /// Random rucksacks as long as the real ones. Each group of three gets a badge and each
+    /// rucksack its own letters, one of which it puts in both compartments.
+    fn synthesise(data: &str, rng: &mut Rng) -> Result<String, &'static str> {
+        let lines = data.lines().collect::<Vec<_>>();
+        let mut rucksacks = Vec::new();
+        for group in lines.chunks(3) {
+            let mut letters = ('a'..='z').chain('A'..='Z').collect::<Vec<_>>();
+            rng.shuffle(&mut letters);
+            let badge = letters[0];
+            for (i, line) in group.iter().enumerate() {
+                let own = &letters[1 + 17 * i..][..17];
+                let (shared, left, right) = (own[0], &own[1..9], &own[9..]);
+                let length = line.len();
+                let half = length / 2;
+                if half == 0 {
+                    rucksacks.push(badge.to_string().repeat(length));
+                    continue;
+                }
+
+                let mut first = if half == 1 {
+                    vec![badge]
+                } else {
+                    let mut first = vec![shared, badge];
+                    first.extend((2..half).map(|_| *rng.choose(left)));
+                    first
+                };
+                let mut second = vec![first[0]];
+                second.extend((1..length - half).map(|_| *rng.choose(right)));
+                rng.shuffle(&mut first);
+                rng.shuffle(&mut second);
+                rucksacks.push(first.into_iter().chain(second).collect::<String>());
+            }
+        }
+
+        let mut out = rucksacks.join("\n");
+        if data.ends_with('\n') {
+            out.push('\n');
+        }
+        Ok(out)
+    }
 }
 
-fn get_priority(item: char) -> i32 {
+/// The priority of an item: 1 to 26 for a to z and 27 to 52 for A to Z.
+pub fn get_priority(item: char) -> i32 {
     match item {
         'a'..='z' => item as i32 - 97 + 1,
         'A'..='Z' => item as i32 - 65 + 27,
@@ -32,25 +120,29 @@ fn get_priority(item: char) -> i32 {
     }
 }
 
-fn rucksack_priority(rucksack: &str) -> i32 {
+/// The priority of the item that is in both halves of `rucksack`, if there is one.
+pub fn rucksack_priority(rucksack: &str) -> Option<i32> {
     let length = rucksack.chars().count() / 2;
     let mut existing = HashSet::new();
     for (index, item) in rucksack.char_indices() {
         if index < length {
             existing.insert(item);
-        } else {
-            if existing.get(&item).is_some() {
-                return get_priority(item);
-            }
+        } else if existing.contains(&item) {
+            return Some(get_priority(item));
         }
     }
-    unreachable!()
+    None
 }
 
-fn badge_item_type(group: &[&str]) -> char {
-    let set1 = group[0].chars().collect::<HashSet<_>>();
-    let set2 = group[1].chars().collect::<HashSet<_>>();
-    let set3 = group[2].chars().collect::<HashSet<_>>();
+/// The one item every rucksack in `group`, a group of three, has. None if they share no item
+/// or more than one.
+pub fn badge_item_type(group: &[&str]) -> Option<char> {
+    let [first, second, third] = group else {
+        return None;
+    };
+    let set1 = first.chars().collect::<HashSet<_>>();
+    let set2 = second.chars().collect::<HashSet<_>>();
+    let set3 = third.chars().collect::<HashSet<_>>();
 
     let intersection = set1
         .intersection(&set2)
@@ -59,9 +151,11 @@ fn badge_item_type(group: &[&str]) -> char {
         .intersection(&set3)
         .copied()
         .collect::<HashSet<_>>();
-    assert_eq!(intersection.len(), 1);
+    if intersection.len() != 1 {
+        return None;
+    }
 
-    intersection.into_iter().next().unwrap()
+    intersection.into_iter().next()
 }
 
 #[cfg(test)]
@@ -87,18 +181,42 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_rucksack_priority() {
-        let actual = TEST_DATA
-            .lines()
-            .map(|x| rucksack_priority(x))
-            .collect::<Vec<_>>();
-        let expected = vec![16, 38, 42, 22, 20, 19];
+        let actual = TEST_DATA.lines().map(rucksack_priority).collect::<Vec<_>>();
+        let expected = [16, 38, 42, 22, 20, 19].map(Some);
         assert_eq!(actual, expected);
+        assert_eq!(rucksack_priority("ab"), None);
     }
 
     #[test]
     fn test_badge_item_type() {
         let group = TEST_DATA.lines().take(3).collect::<Vec<_>>();
         let expected = badge_item_type(&group);
-        assert_eq!(expected, 'r');
+        assert_eq!(expected, Some('r'));
+        assert_eq!(badge_item_type(&group[..2]), None);
+        assert_eq!(badge_item_type(&["ab", "ab", "ab"]), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Day03::parse("aa\nab\naa"),
+            Err(ParseError::new(
+                2,
+                1,
+                "expected an item in both compartments"
+            ))
+        );
+        assert_eq!(
+            Day03::parse("aa\naa\naa\nbb"),
+            Err(ParseError::new(4, 3, "expected a group of three rucksacks"))
+        );
+        assert_eq!(
+            Day03::parse("abab\nabab\nabab"),
+            Err(ParseError::new(
+                1,
+                1,
+                "expected the group to share exactly one item"
+            ))
+        );
     }
 }
@@ -1,36 +1,71 @@
-const DATA: &str = include_str!("../../data/year2022/day04.txt");
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run_part1() {
-    let data = parse_data(DATA);
-    let count = fully_contained_pair_count(&data);
-    println!("fully contained: {}", count);
-}
+/// The section ranges of two elves, each from its first to its last section.
+pub type Pair = ((i32, i32), (i32, i32));
+
+/// Camp Cleanup: how many pairs of elves have one section range inside the other, and how many
+/// have ranges that overlap at all.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2022::day04::Day04;
+///
+/// let pairs = Day04::parse("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8")?;
+/// assert_eq!(Day04::part1(&pairs), 2);
+/// assert_eq!(Day04::part2(&pairs), 4);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input<'a> = Vec<Pair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-pub fn run_part2() {
-    let data = parse_data(DATA);
-    let count = overlap_count(&data);
-    println!("overlap: {}", count);
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
+
+    fn part1(pairs: &Self::Input<'_>) -> usize {
+        fully_contained_pair_count(pairs)
+    }
+
+    fn part2(pairs: &Self::Input<'_>) -> usize {
+        overlap_count(pairs)
+    }
 }
 
-fn fully_contained_pair_count(pairs: &[((i32, i32), (i32, i32))]) -> usize {
+/// Counts the pairs where one range contains the other.
+pub fn fully_contained_pair_count(pairs: &[Pair]) -> usize {
     pairs
         .iter()
         .filter(|(elf1, elf2)| is_fully_contained(*elf1, *elf2))
         .count()
 }
 
-fn overlap_count(pairs: &[((i32, i32), (i32, i32))]) -> usize {
+/// Counts the pairs whose ranges overlap.
+pub fn overlap_count(pairs: &[Pair]) -> usize {
     pairs
         .iter()
         .filter(|(elf1, elf2)| has_overlap(*elf1, *elf2))
         .count()
 }
 
-fn is_fully_contained(elf1: (i32, i32), elf2: (i32, i32)) -> bool {
+/// Whether either range contains the other.
+pub fn is_fully_contained(elf1: (i32, i32), elf2: (i32, i32)) -> bool {
     (elf1.0 <= elf2.0 && elf1.1 >= elf2.1) || (elf2.0 <= elf1.0 && elf2.1 >= elf1.1)
 }
 
-fn has_overlap(elf1: (i32, i32), elf2: (i32, i32)) -> bool {
+/// Whether the ranges share at least one section.
+///
+/// ```
+/// use aoc::year2022::day04::has_overlap;
+///
+/// assert!(has_overlap((5, 7), (7, 9)));
+/// assert!(!has_overlap((2, 4), (6, 8)));
+/// ```
+pub fn has_overlap(elf1: (i32, i32), elf2: (i32, i32)) -> bool {
     let (l1, h1) = elf1;
     let (l2, h2) = elf2;
     // l1 l2 h1
@@ -43,14 +78,16 @@ fn has_overlap(elf1: (i32, i32), elf2: (i32, i32)) -> bool {
     || (l2 <= h1 && h1 <= h2)
 }
 
-fn parse_data(data: &str) -> Vec<((i32, i32), (i32, i32))> {
+fn parse_data(data: &str) -> Result<Vec<Pair>, ParseError> {
+    let range = |elf| {
+        let (low, high) = error::split_once(data, elf, '-')?;
+        Ok((error::number(data, low)?, error::number(data, high)?))
+    };
     data.lines()
         .map(|l| {
-            l.split(|c| c == '-' || c == ',')
-                .map(|c| c.parse().unwrap())
-                .collect::<Vec<_>>()
+            let (elf1, elf2) = error::split_once(data, l, ',')?;
+            Ok((range(elf1)?, range(elf2)?))
         })
-        .map(|l| ((l[0], l[1]), (l[2], l[3])))
         .collect()
 }
 
@@ -76,7 +113,7 @@ mod tests {
 
     #[test]
     fn test_fully_contained_pair_count() {
-        let pairs = parse_data(TEST_DATA);
+        let pairs = parse_data(TEST_DATA).unwrap();
         assert_eq!(fully_contained_pair_count(&pairs), 2);
     }
 
@@ -90,7 +127,19 @@ mod tests {
 
     #[test]
     fn test_overlap_count() {
-        let data = parse_data(TEST_DATA);
+        let data = parse_data(TEST_DATA).unwrap();
         assert_eq!(overlap_count(&data), 4);
     }
+
+    #[test]
+    fn test_parse_data_errors() {
+        assert_eq!(
+            parse_data("2-4,6-8\n2-3,4_5"),
+            Err(ParseError::new(2, 8, "expected '-'"))
+        );
+        assert_eq!(
+            parse_data("2-4,6-x"),
+            Err(ParseError::new(1, 7, "expected a number, found \"x\""))
+        );
+    }
 }
@@ -1,43 +1,67 @@
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
 
-const DATA: &str = include_str!("../../data/year2024/day01.txt");
+/// Historian Hysteria: how far apart the two lists of location IDs are, and how similar.
+///
+/// ```
+/// use aoc::prelude::*;
+/// use aoc::year2024::day01::Day01;
+///
+/// let lists = Day01::parse("3   4\n4   3\n2   5\n1   3\n3   9\n3   3")?;
+/// assert_eq!(Day01::part1(&lists), 11);
+/// assert_eq!(Day01::part2(&lists), 31);
+/// # Ok::<(), ParseError>(())
+/// ```
+pub struct Day01;
 
-pub fn run_part1() {
-    let input = parse_data(DATA);
-    let distance = get_total_distance(input);
-    println!("Total distance: {}", distance);
-}
+impl Solution for Day01 {
+    type Input<'a> = (Vec<usize>, Vec<usize>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
 
-pub fn run_part2() {
-    let input = parse_data(DATA);
-    let similarity_score = get_similarity_score(input);
-    println!("Similarity score: {}", similarity_score);
+    fn part1(input: &Self::Input<'_>) -> usize {
+        get_total_distance(input.clone())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> usize {
+        get_similarity_score(input.clone())
+    }
 }
 
-fn parse_data(data: &str) -> (Vec<usize>, Vec<usize>) {
+fn parse_data(data: &str) -> Result<(Vec<usize>, Vec<usize>), ParseError> {
     data.lines()
         .map(|l| {
             let mut parts = l.split_whitespace();
-            (
-                parts.next().unwrap().parse::<usize>().unwrap(),
-                parts.next().unwrap().parse::<usize>().unwrap(),
-            )
+            let mut next = || {
+                let part = parts
+                    .next()
+                    .ok_or_else(|| ParseError::after(data, l, "expected two numbers"))?;
+                error::number::<usize>(data, part)
+            };
+            Ok((next()?, next()?))
         })
         .collect()
 }
 
-fn get_total_distance(mut input: (Vec<usize>, Vec<usize>)) -> usize {
+/// Pairs the lists up smallest to smallest and adds up how far apart each pair is.
+pub fn get_total_distance(mut input: (Vec<usize>, Vec<usize>)) -> usize {
     input.0.sort();
     input.1.sort();
     let a = input
         .0
         .into_iter()
-        .zip(input.1.into_iter())
+        .zip(input.1)
         .map(|(left, right)| left.abs_diff(right));
     a.sum::<usize>()
 }
 
-fn get_similarity_score(input: (Vec<usize>, Vec<usize>)) -> usize {
+/// Adds up each number in the left list times how often it appears in the right one.
+pub fn get_similarity_score(input: (Vec<usize>, Vec<usize>)) -> usize {
     let left = count_instances(&input.0);
     let right = count_instances(&input.1);
     left.into_iter()
@@ -45,7 +69,7 @@ fn get_similarity_score(input: (Vec<usize>, Vec<usize>)) -> usize {
         .sum()
 }
 
-fn count_instances(input: &Vec<usize>) -> HashMap<usize, usize> {
+fn count_instances(input: &[usize]) -> HashMap<usize, usize> {
     input.iter().fold(HashMap::new(), |mut acc, &x| {
         *acc.entry(x).or_insert(0) += 1;
         acc
@@ -66,20 +90,29 @@ mod tests {
 
     #[test]
     fn test_parse_data() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         assert_eq!(input, (vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3]));
+
+        assert_eq!(
+            parse_data("3   4\n4"),
+            Err(ParseError::new(2, 2, "expected two numbers"))
+        );
+        assert_eq!(
+            parse_data("3   4\n4   -3"),
+            Err(ParseError::new(2, 5, "expected a number, found \"-3\""))
+        );
     }
 
     #[test]
     fn test_get_total_distance() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         let distance = get_total_distance(input);
         assert_eq!(distance, 11);
     }
 
     #[test]
     fn test_get_similarity_score() {
-        let input = parse_data(TEST_DATA);
+        let input = parse_data(TEST_DATA).unwrap();
         let similarity_score = get_similarity_score(input);
         assert_eq!(similarity_score, 31);
     }